@@ -0,0 +1,458 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, U128, U256,
+};
+
+use crate::dict::{get_key, key_and_value_to_str, set_key, Dict};
+use crate::error::BribeError;
+use crate::escrow;
+use crate::event::{emit, BribeEvent};
+use crate::token::{self, TokenStandard};
+use crate::utils::{self, current_block_timestamp_seconds, require, WEEK};
+use crate::voter;
+
+pub const VOTER: &str = "voter";
+pub const VOTING_ESCROW: &str = "voting_escrow";
+pub const REWARDS: &str = "rewards";
+pub const TOTAL_SUPPLY: &str = "total_supply";
+pub const BALANCES: &str = "balances";
+pub const CHECKPOINTS: &str = "checkpoints";
+pub const NUM_CHECKPOINTS: &str = "num_checkpoints";
+pub const SUPPLY_CHECKPOINTS: &str = "supply_checkpoints";
+pub const SUPPLY_NUM_CHECKPOINTS: &str = "supply_num_checkpoints";
+pub const TOKEN_REWARDS_PER_EPOCH: &str = "token_rewards_per_epoch";
+pub const LAST_EARN: &str = "last_earn";
+/// Standard of each reward token, as the voter reported it when the token was first notified
+pub const REWARD_STANDARDS: &str = "reward_standards";
+
+pub const ARG_VOTER: &str = "voter";
+pub const ARG_VOTING_ESCROW: &str = "voting_escrow";
+pub const ARG_TOKEN_ID: &str = "token_id";
+pub const ARG_AMOUNT: &str = "amount";
+pub const ARG_TOKEN: &str = "token";
+pub const ARG_TOKENS: &str = "tokens";
+pub const ARG_EPOCH: &str = "epoch";
+
+/// Most distinct reward tokens a bribe accepts, new ones must be whitelisted by the voter
+pub const MAX_REWARDS: usize = 16;
+/// Most epochs walked by a single claim, later epochs are picked up by the next claim
+pub const MAX_CLAIM_EPOCHS: u64 = 50;
+
+/// A balance and the time it was written, `(timestamp, balance)`
+type Checkpoint = (u64, U128);
+
+/// @param voter Package hash of the voter, the only caller allowed to move vote balances
+/// @param voting_escrow Package hash of the escrow, used to authorise claims
+pub fn init(voter: Key, voting_escrow: Key) {
+    set_key(VOTER, voter);
+    set_key(VOTING_ESCROW, voting_escrow);
+    set_key(REWARDS, Vec::<Key>::new());
+    set_key(TOTAL_SUPPLY, U128::zero());
+    set_key(SUPPLY_NUM_CHECKPOINTS, 0u64);
+    Dict::init(BALANCES);
+    Dict::init(CHECKPOINTS);
+    Dict::init(NUM_CHECKPOINTS);
+    Dict::init(SUPPLY_CHECKPOINTS);
+    Dict::init(TOKEN_REWARDS_PER_EPOCH);
+    Dict::init(LAST_EARN);
+    Dict::init(REWARD_STANDARDS);
+}
+
+fn epoch_start(timestamp: u64) -> u64 {
+    timestamp / WEEK * WEEK
+}
+
+fn _reward_standard(token: &Key) -> TokenStandard {
+    let standard: u8 = Dict::instance(REWARD_STANDARDS)
+        .get_by_key(token)
+        .unwrap_or_revert();
+    TokenStandard::require_from_u8(standard)
+}
+
+fn only_voter() {
+    let voter: Key = get_key(VOTER).unwrap_or_revert();
+    require(utils::get_immediate_caller_key() == voter, BribeError::NotVoter);
+}
+
+fn total_supply() -> u128 {
+    let supply: U128 = get_key(TOTAL_SUPPLY).unwrap_or_revert();
+    supply.as_u128()
+}
+
+fn _balance_of(token_id: u64) -> u128 {
+    let balance: U128 = Dict::instance(BALANCES)
+        .get(&token_id.to_string())
+        .unwrap_or_default();
+    balance.as_u128()
+}
+
+fn _rewards_per_epoch(token: &Key, epoch: u64) -> u128 {
+    let amount: U128 = Dict::instance(TOKEN_REWARDS_PER_EPOCH)
+        .get(&key_and_value_to_str(token, &epoch))
+        .unwrap_or_default();
+    amount.as_u128()
+}
+
+fn _last_earn(token: &Key, token_id: u64) -> u64 {
+    Dict::instance(LAST_EARN)
+        .get(&key_and_value_to_str(token, &token_id))
+        .unwrap_or(0)
+}
+
+////////////////////////////////////////////////////////////////
+//                             CHECKPOINTS
+//////////////////////////////////////////////////////////////*/
+fn checkpoint_id(token_id: u64, index: u64) -> String {
+    format!("{}_{}", token_id, index)
+}
+
+fn _num_checkpoints(token_id: u64) -> u64 {
+    Dict::instance(NUM_CHECKPOINTS)
+        .get(&token_id.to_string())
+        .unwrap_or(0)
+}
+
+fn _checkpoint(token_id: u64, index: u64) -> Checkpoint {
+    Dict::instance(CHECKPOINTS)
+        .get(&checkpoint_id(token_id, index))
+        .unwrap_or_default()
+}
+
+fn _supply_num_checkpoints() -> u64 {
+    get_key(SUPPLY_NUM_CHECKPOINTS).unwrap_or_revert()
+}
+
+fn _supply_checkpoint(index: u64) -> Checkpoint {
+    Dict::instance(SUPPLY_CHECKPOINTS)
+        .get(&index.to_string())
+        .unwrap_or_default()
+}
+
+/// @notice Index of the last checkpoint written at or before `timestamp`, 0 if there is none
+fn _prior_index(
+    num_checkpoints: u64,
+    timestamp: u64,
+    checkpoint: impl Fn(u64) -> Checkpoint,
+) -> u64 {
+    if num_checkpoints == 0 {
+        return 0;
+    }
+    if checkpoint(num_checkpoints - 1).0 <= timestamp {
+        return num_checkpoints - 1;
+    }
+    if checkpoint(0).0 > timestamp {
+        return 0;
+    }
+    let mut lower = 0u64;
+    let mut upper = num_checkpoints - 1;
+    while upper > lower {
+        let center = upper - (upper - lower) / 2;
+        let ts = checkpoint(center).0;
+        if ts == timestamp {
+            return center;
+        } else if ts < timestamp {
+            lower = center;
+        } else {
+            upper = center - 1;
+        }
+    }
+    lower
+}
+
+fn _prior_balance_index(token_id: u64, timestamp: u64) -> u64 {
+    _prior_index(_num_checkpoints(token_id), timestamp, |index| _checkpoint(token_id, index))
+}
+
+fn _prior_supply_index(timestamp: u64) -> u64 {
+    _prior_index(_supply_num_checkpoints(), timestamp, _supply_checkpoint)
+}
+
+fn _write_checkpoint(token_id: u64, balance: u128) {
+    let now = current_block_timestamp_seconds();
+    let num = _num_checkpoints(token_id);
+    let dict = Dict::instance(CHECKPOINTS);
+    if num > 0 && _checkpoint(token_id, num - 1).0 == now {
+        dict.set(&checkpoint_id(token_id, num - 1), (now, U128::from(balance)));
+    } else {
+        dict.set(&checkpoint_id(token_id, num), (now, U128::from(balance)));
+        Dict::instance(NUM_CHECKPOINTS).set(&token_id.to_string(), num + 1);
+    }
+}
+
+fn _write_supply_checkpoint() {
+    let now = current_block_timestamp_seconds();
+    let num = _supply_num_checkpoints();
+    let dict = Dict::instance(SUPPLY_CHECKPOINTS);
+    let supply = U128::from(total_supply());
+    if num > 0 && _supply_checkpoint(num - 1).0 == now {
+        dict.set(&(num - 1).to_string(), (now, supply));
+    } else {
+        dict.set(&num.to_string(), (now, supply));
+        set_key(SUPPLY_NUM_CHECKPOINTS, num + 1);
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                             VOTES
+//////////////////////////////////////////////////////////////*/
+/// @notice Record `amount` of votes cast by `token_id`, only the voter may call
+#[no_mangle]
+pub extern "C" fn deposit() {
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let amount = amount.as_u128();
+    only_voter();
+
+    set_key(TOTAL_SUPPLY, U128::from(total_supply() + amount));
+    let balance = _balance_of(token_id) + amount;
+    Dict::instance(BALANCES).set(&token_id.to_string(), U128::from(balance));
+    _write_checkpoint(token_id, balance);
+    _write_supply_checkpoint();
+    emit(&BribeEvent::Deposit { token_id, amount });
+}
+
+/// @notice Remove `amount` of votes of `token_id`, only the voter may call
+#[no_mangle]
+pub extern "C" fn withdraw() {
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let amount = amount.as_u128();
+    only_voter();
+
+    set_key(TOTAL_SUPPLY, U128::from(total_supply() - amount));
+    let balance = _balance_of(token_id) - amount;
+    Dict::instance(BALANCES).set(&token_id.to_string(), U128::from(balance));
+    _write_checkpoint(token_id, balance);
+    _write_supply_checkpoint();
+    emit(&BribeEvent::Withdraw { token_id, amount });
+}
+
+////////////////////////////////////////////////////////////////
+//                             REWARDS
+//////////////////////////////////////////////////////////////*/
+/// @notice Add `amount` of `token` to the rewards of the current epoch, shared by the
+///         veNFTs voting for the pool when the epoch ends
+#[no_mangle]
+pub extern "C" fn notify_reward_amount() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let amount = amount.as_u128();
+    require(amount > 0, BribeError::InvalidAmount);
+
+    let mut rewards: Vec<Key> = get_key(REWARDS).unwrap_or_revert();
+    if !rewards.contains(&token) {
+        let voter_package: Key = get_key(VOTER).unwrap_or_revert();
+        require(voter::is_whitelisted(voter_package, token), BribeError::NotWhitelisted);
+        require(rewards.len() < MAX_REWARDS, BribeError::TooManyRewards);
+        let standard = voter::token_standard(voter_package, token);
+        Dict::instance(REWARD_STANDARDS).set_by_key(&token, standard);
+        rewards.push(token);
+        set_key(REWARDS, rewards);
+    }
+
+    let sender = utils::get_immediate_caller_key();
+    token::transfer_from(
+        token,
+        _reward_standard(&token),
+        sender,
+        utils::get_self_key(),
+        amount,
+    );
+
+    let epoch = epoch_start(current_block_timestamp_seconds());
+    Dict::instance(TOKEN_REWARDS_PER_EPOCH).set(
+        &key_and_value_to_str(&token, &epoch),
+        U128::from(_rewards_per_epoch(&token, epoch) + amount),
+    );
+    emit(&BribeEvent::NotifyReward {
+        from: sender,
+        reward: token,
+        epoch,
+        amount,
+    });
+}
+
+/// @notice Rewards of `token` earned by `token_id` over the finished epochs since its last
+///         claim, and the epoch the walk stopped at
+fn _earned(token: &Key, token_id: u64) -> (u128, u64) {
+    let now_epoch = epoch_start(current_block_timestamp_seconds());
+    if _num_checkpoints(token_id) == 0 {
+        return (0, now_epoch);
+    }
+    let mut current = epoch_start(_last_earn(token, token_id));
+    let first = _checkpoint(token_id, _prior_balance_index(token_id, current));
+    // The last claim may predate the first vote
+    current = current.max(epoch_start(first.0));
+
+    let mut reward = 0u128;
+    let mut walked = 0u64;
+    while current < now_epoch && walked < MAX_CLAIM_EPOCHS {
+        let epoch_end = current + WEEK - 1;
+        let balance = _checkpoint(token_id, _prior_balance_index(token_id, epoch_end)).1;
+        let supply = _supply_checkpoint(_prior_supply_index(epoch_end)).1;
+        let supply = supply.as_u128().max(1);
+        let rewards = _rewards_per_epoch(token, current);
+        let share = U256::from(balance.as_u128()) * U256::from(rewards) / U256::from(supply);
+        reward += share.as_u128();
+        current += WEEK;
+        walked += 1;
+    }
+    (reward, current)
+}
+
+/// @notice Claim the rewards of `tokens` earned by `token_id`, paid to the caller
+#[no_mangle]
+pub extern "C" fn get_reward() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let tokens: Vec<Key> = runtime::get_named_arg(ARG_TOKENS);
+    require(tokens.len() <= MAX_REWARDS, BribeError::TooManyTokens);
+    let sender = utils::get_immediate_caller_key();
+    let voting_escrow: Key = get_key(VOTING_ESCROW).unwrap_or_revert();
+    require(
+        escrow::is_approved_or_owner(voting_escrow, sender, token_id),
+        BribeError::NotOwnerOrApproved,
+    );
+
+    let last_earn = Dict::instance(LAST_EARN);
+    for token in tokens.iter() {
+        let (reward, reached) = _earned(token, token_id);
+        // Stay on the epoch the walk stopped at so a capped claim resumes there
+        last_earn.set(&key_and_value_to_str(token, &token_id), reached);
+        if reward > 0 {
+            token::transfer(*token, _reward_standard(token), sender, reward);
+            emit(&BribeEvent::ClaimRewards {
+                from: sender,
+                reward: *token,
+                amount: reward,
+            });
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                             VIEWS
+//////////////////////////////////////////////////////////////*/
+#[no_mangle]
+pub extern "C" fn earned() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let (reward, _) = _earned(&token, token_id);
+    runtime::ret(CLValue::from_t(U128::from(reward)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn balance_of() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    runtime::ret(CLValue::from_t(U128::from(_balance_of(token_id))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_total_supply() {
+    runtime::ret(CLValue::from_t(U128::from(total_supply())).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn rewards() {
+    let rewards: Vec<Key> = get_key(REWARDS).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(rewards).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn token_rewards_per_epoch() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let epoch: u64 = runtime::get_named_arg(ARG_EPOCH);
+    let amount = _rewards_per_epoch(&token, epoch_start(epoch));
+    runtime::ret(CLValue::from_t(U128::from(amount)).unwrap_or_revert());
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    for name in ["deposit", "withdraw"].iter() {
+        entry_points.add_entry_point(EntryPoint::new(
+            *name,
+            vec![
+                Parameter::new(ARG_AMOUNT, U128::cl_type()),
+                Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            ],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "notify_reward_amount",
+        vec![
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_AMOUNT, U128::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_reward",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_TOKENS, Vec::<Key>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "earned",
+        vec![
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_total_supply",
+        vec![],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "rewards",
+        vec![],
+        Vec::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "token_rewards_per_epoch",
+        vec![
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_EPOCH, CLType::U64),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
@@ -0,0 +1,18 @@
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum BribeError {
+    NotVoter = 1,
+    NotOwnerOrApproved = 2,
+    InvalidAmount = 3,
+    TooManyRewards = 4,
+    TooManyTokens = 5,
+    NotWhitelisted = 6,
+}
+
+impl From<BribeError> for ApiError {
+    fn from(e: BribeError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
@@ -0,0 +1,38 @@
+//! Calls into the voting escrow, addressed by its package hash so escrow upgrades are followed
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+
+fn package(escrow: Key) -> ContractPackageHash {
+    ContractPackageHash::new(escrow.into_hash().unwrap_or_revert())
+}
+
+pub fn owner_of(escrow: Key, token_id: u64) -> Option<Key> {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "owner_of",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn get_approved(escrow: Key, owner: Key, token_id: u64) -> Option<Key> {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "get_approved",
+        runtime_args! {
+            "owner" => owner,
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn is_approved_or_owner(escrow: Key, spender: Key, token_id: u64) -> bool {
+    match owner_of(escrow, token_id) {
+        Some(owner) if owner == spender => true,
+        Some(owner) => get_approved(escrow, owner, token_id) == Some(spender),
+        None => false,
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::{collections::BTreeMap, string::ToString};
+use casper_contract::contract_api::storage;
+use casper_types::{Key, URef};
+
+use crate::utils;
+
+pub enum BribeEvent {
+    Deposit {
+        token_id: u64,
+        amount: u128,
+    },
+    Withdraw {
+        token_id: u64,
+        amount: u128,
+    },
+    NotifyReward {
+        from: Key,
+        reward: Key,
+        epoch: u64,
+        amount: u128,
+    },
+    ClaimRewards {
+        from: Key,
+        reward: Key,
+        amount: u128,
+    },
+}
+
+pub fn emit(event: &BribeEvent) {
+    let mut param = BTreeMap::new();
+    param.insert("contract_package_hash", utils::get_self_key().to_string());
+    match event {
+        BribeEvent::Deposit { token_id, amount } => {
+            param.insert("event_type", "bribe_deposit".to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        BribeEvent::Withdraw { token_id, amount } => {
+            param.insert("event_type", "bribe_withdraw".to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        BribeEvent::NotifyReward {
+            from,
+            reward,
+            epoch,
+            amount,
+        } => {
+            param.insert("event_type", "bribe_notify_reward".to_string());
+            param.insert("from", from.to_string());
+            param.insert("reward", reward.to_string());
+            param.insert("epoch", epoch.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        BribeEvent::ClaimRewards {
+            from,
+            reward,
+            amount,
+        } => {
+            param.insert("event_type", "bribe_claim_rewards".to_string());
+            param.insert("from", from.to_string());
+            param.insert("reward", reward.to_string());
+            param.insert("amount", amount.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
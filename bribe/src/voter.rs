@@ -0,0 +1,29 @@
+//! Calls into the voter, addressed by its package hash
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs};
+
+fn package(voter: Key) -> ContractPackageHash {
+    ContractPackageHash::new(voter.into_hash().unwrap_or_revert())
+}
+
+pub fn is_whitelisted(voter: Key, token: Key) -> bool {
+    runtime::call_versioned_contract(
+        package(voter),
+        None,
+        "is_whitelisted",
+        runtime_args! {
+            "token" => token
+        },
+    )
+}
+
+pub fn token_standard(voter: Key, token: Key) -> u8 {
+    runtime::call_versioned_contract(
+        package(voter),
+        None,
+        "token_standard",
+        runtime_args! {
+            "token" => token
+        },
+    )
+}
@@ -0,0 +1,8 @@
+//! Storage, call stack and token helpers shared by the distributor, minter, voter, gauge,
+//! bribe and governor contracts
+#![no_std]
+extern crate alloc;
+
+pub mod dict;
+pub mod token;
+pub mod utils;
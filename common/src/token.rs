@@ -0,0 +1,146 @@
+//! Calls into fungible tokens of either standard the escrow accepts. Amounts are U256 on
+//! the token side and u128 in contract accounting.
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::FromBytes, runtime_args, ApiError, CLTyped, ContractHash, ContractPackageHash, Key,
+    RuntimeArgs, U256,
+};
+
+use crate::utils::require;
+
+/// Codes sit above the contract errors of the crates using this module
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum TokenError {
+    InvalidTokenStandard = 100,
+    TokenAmountOverflow = 101,
+}
+
+impl From<TokenError> for ApiError {
+    fn from(e: TokenError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
+
+/// Same values as the escrow's `token_standard` install argument
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum TokenStandard {
+    /// casper-ecosystem ERC-20 example, called by contract hash
+    Erc20 = 0,
+    /// CEP-18, called by package hash so token upgrades are picked up
+    Cep18 = 1,
+}
+
+impl TokenStandard {
+    pub fn from_u8(value: u8) -> Option<TokenStandard> {
+        match value {
+            0 => Some(TokenStandard::Erc20),
+            1 => Some(TokenStandard::Cep18),
+            _ => None,
+        }
+    }
+
+    /// Reverts with `InvalidTokenStandard` for an unknown value, e.g. a bad install argument
+    pub fn require_from_u8(value: u8) -> TokenStandard {
+        TokenStandard::from_u8(value).unwrap_or_revert_with(TokenError::InvalidTokenStandard)
+    }
+}
+
+fn call_token<T: CLTyped + FromBytes>(
+    token: Key,
+    standard: TokenStandard,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> T {
+    let hash = token.into_hash().unwrap_or_revert();
+    match standard {
+        TokenStandard::Erc20 => runtime::call_contract(ContractHash::new(hash), entry_point, args),
+        TokenStandard::Cep18 => runtime::call_versioned_contract(
+            ContractPackageHash::new(hash),
+            None,
+            entry_point,
+            args,
+        ),
+    }
+}
+
+pub fn to_u128(amount: U256) -> u128 {
+    require(
+        amount <= U256::from(u128::MAX),
+        TokenError::TokenAmountOverflow,
+    );
+    amount.as_u128()
+}
+
+pub fn total_supply(token: Key, standard: TokenStandard) -> u128 {
+    let supply: U256 = call_token(token, standard, "total_supply", runtime_args! {});
+    to_u128(supply)
+}
+
+pub fn balance_of(token: Key, standard: TokenStandard, address: Key) -> u128 {
+    let balance: U256 = call_token(
+        token,
+        standard,
+        "balance_of",
+        runtime_args! {
+            "address" => address
+        },
+    );
+    to_u128(balance)
+}
+
+pub fn transfer(token: Key, standard: TokenStandard, recipient: Key, amount: u128) {
+    let _: () = call_token(
+        token,
+        standard,
+        "transfer",
+        runtime_args! {
+            "recipient" => recipient,
+            "amount" => U256::from(amount)
+        },
+    );
+}
+
+pub fn transfer_from(
+    token: Key,
+    standard: TokenStandard,
+    owner: Key,
+    recipient: Key,
+    amount: u128,
+) {
+    let _: () = call_token(
+        token,
+        standard,
+        "transfer_from",
+        runtime_args! {
+            "owner" => owner,
+            "recipient" => recipient,
+            "amount" => U256::from(amount)
+        },
+    );
+}
+
+pub fn approve(token: Key, standard: TokenStandard, spender: Key, amount: u128) {
+    let _: () = call_token(
+        token,
+        standard,
+        "approve",
+        runtime_args! {
+            "spender" => spender,
+            "amount" => U256::from(amount)
+        },
+    );
+}
+
+/// @dev Unlike the other entry points, `mint` names its recipient differently per standard
+pub fn mint(token: Key, standard: TokenStandard, to: Key, amount: u128) {
+    let recipient_arg = match standard {
+        TokenStandard::Erc20 => "to",
+        TokenStandard::Cep18 => "owner",
+    };
+    let mut args = RuntimeArgs::new();
+    args.insert(recipient_arg, to).unwrap_or_revert();
+    args.insert("amount", U256::from(amount)).unwrap_or_revert();
+    let _: () = call_token(token, standard, "mint", args);
+}
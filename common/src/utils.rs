@@ -0,0 +1,47 @@
+use casper_contract::{
+    contract_api::runtime::{self, get_blocktime},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{system::CallStackElement, ApiError, Key};
+
+pub const WEEK: u64 = 7 * 24 * 60 * 60;
+
+pub fn current_block_timestamp_seconds() -> u64 {
+    u64::from(get_blocktime()) / 1000
+}
+
+pub fn get_self_key() -> Key {
+    runtime::get_call_stack()
+        .into_iter()
+        .rev()
+        .next()
+        .map(call_stack_element_to_key)
+        .unwrap_or_revert()
+}
+
+pub fn get_immediate_caller_key() -> Key {
+    runtime::get_call_stack()
+        .into_iter()
+        .rev()
+        .nth(1)
+        .map(call_stack_element_to_key)
+        .unwrap_or_revert()
+}
+
+fn call_stack_element_to_key(call_stack_element: CallStackElement) -> Key {
+    match call_stack_element {
+        CallStackElement::Session { account_hash } => Key::from(account_hash),
+        CallStackElement::StoredSession { account_hash, .. } => Key::from(account_hash),
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        } => Key::from(contract_package_hash),
+    }
+}
+
+/// Revert with the contract's own error `e` unless `v` holds
+pub fn require<E: Into<ApiError>>(v: bool, e: E) {
+    if !v {
+        runtime::revert(e);
+    }
+}
@@ -0,0 +1,119 @@
+#![no_main]
+#![no_std]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, string::String};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, ContractHash, ContractPackageHash, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef,
+};
+use gauge::gauge::{
+    init, ARG_REWARD_STANDARD, ARG_REWARD_TOKEN, ARG_STAKE, ARG_STAKE_STANDARD, ARG_VOTER,
+    ARG_VOTING_ESCROW,
+};
+
+#[no_mangle]
+fn constructor() {
+    let stake: Key = runtime::get_named_arg(ARG_STAKE);
+    let reward_token: Key = runtime::get_named_arg(ARG_REWARD_TOKEN);
+    let voting_escrow: Key = runtime::get_named_arg(ARG_VOTING_ESCROW);
+    let voter: Key = runtime::get_named_arg(ARG_VOTER);
+    let stake_standard: u8 = runtime::get_named_arg(ARG_STAKE_STANDARD);
+    let reward_standard: u8 = runtime::get_named_arg(ARG_REWARD_STANDARD);
+    init(
+        stake,
+        reward_token,
+        voting_escrow,
+        voter,
+        stake_standard,
+        reward_standard,
+    );
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    // Staked and reward tokens with their standards, package hashes of the escrow and voter
+    let stake: Key = runtime::get_named_arg(ARG_STAKE);
+    let reward_token: Key = runtime::get_named_arg(ARG_REWARD_TOKEN);
+    let voting_escrow: Key = runtime::get_named_arg(ARG_VOTING_ESCROW);
+    let voter: Key = runtime::get_named_arg(ARG_VOTER);
+    let stake_standard: u8 = runtime::get_named_arg(ARG_STAKE_STANDARD);
+    let reward_standard: u8 = runtime::get_named_arg(ARG_REWARD_STANDARD);
+
+    let (contract_hash, _) = storage::new_contract(
+        get_entry_points(),
+        None,
+        Some(format!("{}_contract_package_hash", contract_name)),
+        Some(format!("{}_contract_package_access", contract_name)),
+    );
+
+    let package_hash: ContractPackageHash = ContractPackageHash::new(
+        runtime::get_key(&format!("{}_contract_package_hash", contract_name))
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
+
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let _: () = runtime::call_contract(
+        contract_hash,
+        "constructor",
+        runtime_args! {
+            ARG_STAKE => stake,
+            ARG_REWARD_TOKEN => reward_token,
+            ARG_VOTING_ESCROW => voting_escrow,
+            ARG_VOTER => voter,
+            ARG_STAKE_STANDARD => stake_standard,
+            ARG_REWARD_STANDARD => reward_standard
+        },
+    );
+
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    put_contract_hash_keys(&contract_name, contract_hash);
+}
+
+fn put_contract_hash_keys(contract_name: &str, contract_hash: ContractHash) {
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = gauge::gauge::get_entry_points();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new(ARG_STAKE, Key::cl_type()),
+            Parameter::new(ARG_REWARD_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_VOTING_ESCROW, Key::cl_type()),
+            Parameter::new(ARG_VOTER, Key::cl_type()),
+            Parameter::new(ARG_STAKE_STANDARD, u8::cl_type()),
+            Parameter::new(ARG_REWARD_STANDARD, u8::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
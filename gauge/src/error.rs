@@ -0,0 +1,18 @@
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum GaugeError {
+    NotVoter = 1,
+    InvalidAmount = 2,
+    InsufficientBalance = 3,
+    NotTokenOwner = 4,
+    TokenMismatch = 5,
+    InvalidRewardToken = 6,
+}
+
+impl From<GaugeError> for ApiError {
+    fn from(e: GaugeError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
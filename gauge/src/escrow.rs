@@ -0,0 +1,36 @@
+//! Calls into the voting escrow, addressed by its package hash so escrow upgrades are followed
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128, U256};
+
+fn package(escrow: Key) -> ContractPackageHash {
+    ContractPackageHash::new(escrow.into_hash().unwrap_or_revert())
+}
+
+pub fn owner_of(escrow: Key, token_id: u64) -> Option<Key> {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "owner_of",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn balance_of_nft(escrow: Key, token_id: u64) -> u128 {
+    let balance: U128 = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "balance_of_nft",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    );
+    balance.as_u128()
+}
+
+pub fn ve_total_supply(escrow: Key) -> u128 {
+    let supply: U128 =
+        runtime::call_versioned_contract(package(escrow), None, "ve_total_supply", runtime_args! {});
+    supply.as_u128()
+}
@@ -0,0 +1,64 @@
+use alloc::{collections::BTreeMap, string::ToString};
+use casper_contract::contract_api::storage;
+use casper_types::{Key, URef};
+
+use crate::utils;
+
+pub enum GaugeEvent {
+    Deposit {
+        from: Key,
+        token_id: u64,
+        amount: u128,
+    },
+    Withdraw {
+        from: Key,
+        token_id: u64,
+        amount: u128,
+    },
+    NotifyReward {
+        from: Key,
+        amount: u128,
+    },
+    ClaimRewards {
+        from: Key,
+        amount: u128,
+    },
+}
+
+pub fn emit(event: &GaugeEvent) {
+    let mut param = BTreeMap::new();
+    param.insert("contract_package_hash", utils::get_self_key().to_string());
+    match event {
+        GaugeEvent::Deposit {
+            from,
+            token_id,
+            amount,
+        } => {
+            param.insert("event_type", "gauge_deposit".to_string());
+            param.insert("from", from.to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        GaugeEvent::Withdraw {
+            from,
+            token_id,
+            amount,
+        } => {
+            param.insert("event_type", "gauge_withdraw".to_string());
+            param.insert("from", from.to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        GaugeEvent::NotifyReward { from, amount } => {
+            param.insert("event_type", "gauge_notify_reward".to_string());
+            param.insert("from", from.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        GaugeEvent::ClaimRewards { from, amount } => {
+            param.insert("event_type", "gauge_claim_rewards".to_string());
+            param.insert("from", from.to_string());
+            param.insert("amount", amount.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
@@ -0,0 +1,481 @@
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, U128, U256,
+};
+
+use crate::dict::{get_key, set_key, Dict};
+use crate::error::GaugeError;
+use crate::escrow;
+use crate::event::{emit, GaugeEvent};
+use crate::token::{self, TokenStandard};
+use crate::utils::{self, current_block_timestamp_seconds, require, WEEK};
+use crate::voter;
+
+pub const STAKE: &str = "stake";
+pub const REWARD_TOKEN: &str = "reward_token";
+pub const VOTING_ESCROW: &str = "voting_escrow";
+pub const VOTER: &str = "voter";
+pub const BALANCES: &str = "balances";
+pub const TOTAL_SUPPLY: &str = "total_supply";
+pub const DERIVED_BALANCES: &str = "derived_balances";
+pub const DERIVED_SUPPLY: &str = "derived_supply";
+pub const TOKEN_IDS: &str = "token_ids";
+pub const REWARD_RATE: &str = "reward_rate";
+pub const PERIOD_FINISH: &str = "period_finish";
+pub const LAST_UPDATE_TIME: &str = "last_update_time";
+pub const REWARD_PER_TOKEN_STORED: &str = "reward_per_token_stored";
+pub const USER_REWARD_PER_TOKEN_PAID: &str = "user_reward_per_token_paid";
+pub const REWARDS: &str = "rewards";
+pub const STAKE_STANDARD: &str = "stake_standard";
+pub const REWARD_STANDARD: &str = "reward_standard";
+
+pub const ARG_STAKE: &str = "stake";
+pub const ARG_REWARD_TOKEN: &str = "reward_token";
+pub const ARG_VOTING_ESCROW: &str = "voting_escrow";
+pub const ARG_VOTER: &str = "voter";
+pub const ARG_TOKEN: &str = "token";
+pub const ARG_TOKEN_ID: &str = "token_id";
+pub const ARG_AMOUNT: &str = "amount";
+pub const ARG_ACCOUNT: &str = "account";
+pub const ARG_STAKE_STANDARD: &str = "stake_standard";
+pub const ARG_REWARD_STANDARD: &str = "reward_standard";
+
+/// Rewards notified by the voter are streamed over one week
+pub const DURATION: u64 = WEEK;
+pub const PRECISION: u128 = 1_000_000_000_000_000_000;
+/// Share of the staked balance that counts without any veNFT boost, in percent
+pub const BASE_SHARE: u128 = 40;
+pub const BOOST_SHARE: u128 = 100 - BASE_SHARE;
+
+/// @param stake Hash of the staked LP token
+/// @param reward_token Hash of the token emitted by the voter
+/// @param voting_escrow, voter Package hashes of those contracts
+/// @param stake_standard, reward_standard Standards of the two tokens, as the escrow's
+///        install argument
+pub fn init(
+    stake: Key,
+    reward_token: Key,
+    voting_escrow: Key,
+    voter: Key,
+    stake_standard: u8,
+    reward_standard: u8,
+) {
+    TokenStandard::require_from_u8(stake_standard);
+    TokenStandard::require_from_u8(reward_standard);
+    set_key(STAKE, stake);
+    set_key(REWARD_TOKEN, reward_token);
+    set_key(STAKE_STANDARD, stake_standard);
+    set_key(REWARD_STANDARD, reward_standard);
+    set_key(VOTING_ESCROW, voting_escrow);
+    set_key(VOTER, voter);
+    set_key(TOTAL_SUPPLY, U128::zero());
+    set_key(DERIVED_SUPPLY, U128::zero());
+    set_key(REWARD_RATE, U128::zero());
+    set_key(PERIOD_FINISH, 0u64);
+    set_key(LAST_UPDATE_TIME, 0u64);
+    set_key(REWARD_PER_TOKEN_STORED, U256::zero());
+    Dict::init(BALANCES);
+    Dict::init(DERIVED_BALANCES);
+    Dict::init(TOKEN_IDS);
+    Dict::init(USER_REWARD_PER_TOKEN_PAID);
+    Dict::init(REWARDS);
+}
+
+fn key(name: &str) -> Key {
+    get_key(name).unwrap_or_revert()
+}
+
+fn standard(name: &str) -> TokenStandard {
+    TokenStandard::require_from_u8(get_key(name).unwrap_or_revert())
+}
+
+fn u128_key(name: &str) -> u128 {
+    let value: U128 = get_key(name).unwrap_or_revert();
+    value.as_u128()
+}
+
+fn set_u128_key(name: &str, value: u128) {
+    set_key(name, U128::from(value));
+}
+
+fn account_value(dict_name: &str, account: &Key) -> u128 {
+    let value: U128 = Dict::instance(dict_name)
+        .get_by_key(account)
+        .unwrap_or_default();
+    value.as_u128()
+}
+
+fn set_account_value(dict_name: &str, account: &Key, value: u128) {
+    Dict::instance(dict_name).set_by_key(account, U128::from(value));
+}
+
+fn _token_id(account: &Key) -> u64 {
+    Dict::instance(TOKEN_IDS).get_by_key(account).unwrap_or(0)
+}
+
+////////////////////////////////////////////////////////////////
+//                             BOOST
+//////////////////////////////////////////////////////////////*/
+/// @notice Balance counted for rewards: 40% of the stake plus up to 60% of the
+///         total stake scaled by the share of voting power of the attached veNFT
+fn _derived_balance(account: &Key) -> u128 {
+    let balance = account_value(BALANCES, account);
+    let derived = balance * BASE_SHARE / 100;
+    let token_id = _token_id(account);
+    let mut adjusted = 0u128;
+    if token_id > 0 {
+        let escrow = key(VOTING_ESCROW);
+        let ve_total = escrow::ve_total_supply(escrow);
+        if ve_total > 0 && escrow::owner_of(escrow, token_id) == Some(*account) {
+            let power = escrow::balance_of_nft(escrow, token_id);
+            let scaled = U256::from(u128_key(TOTAL_SUPPLY)) * U256::from(power)
+                / U256::from(ve_total)
+                * U256::from(BOOST_SHARE)
+                / U256::from(100u128);
+            adjusted = scaled.as_u128();
+        }
+    }
+    core::cmp::min(derived + adjusted, balance)
+}
+
+/// @notice Refresh the derived balance of `account` against its current stake and veNFT power
+fn _kick(account: &Key) {
+    let old = account_value(DERIVED_BALANCES, account);
+    let new = _derived_balance(account);
+    set_account_value(DERIVED_BALANCES, account, new);
+    set_u128_key(DERIVED_SUPPLY, u128_key(DERIVED_SUPPLY) - old + new);
+}
+
+/// @notice Anyone may refresh a boost, e.g. once the attached veNFT decayed or was transferred
+#[no_mangle]
+pub extern "C" fn kick() {
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    _update_reward(Some(account));
+    _kick(&account);
+}
+
+////////////////////////////////////////////////////////////////
+//                             REWARDS
+//////////////////////////////////////////////////////////////*/
+fn _last_time_reward_applicable() -> u64 {
+    let period_finish: u64 = get_key(PERIOD_FINISH).unwrap_or_revert();
+    core::cmp::min(current_block_timestamp_seconds(), period_finish)
+}
+
+fn _reward_per_token() -> U256 {
+    let stored: U256 = get_key(REWARD_PER_TOKEN_STORED).unwrap_or_revert();
+    let derived_supply = u128_key(DERIVED_SUPPLY);
+    if derived_supply == 0 {
+        return stored;
+    }
+    let last_update: u64 = get_key(LAST_UPDATE_TIME).unwrap_or_revert();
+    let elapsed = _last_time_reward_applicable().saturating_sub(last_update);
+    stored
+        + U256::from(elapsed) * U256::from(u128_key(REWARD_RATE)) * U256::from(PRECISION)
+            / U256::from(derived_supply)
+}
+
+fn _earned(account: &Key, reward_per_token: U256) -> u128 {
+    let paid: U256 = Dict::instance(USER_REWARD_PER_TOKEN_PAID)
+        .get_by_key(account)
+        .unwrap_or_default();
+    let pending = U256::from(account_value(DERIVED_BALANCES, account))
+        * (reward_per_token - paid)
+        / U256::from(PRECISION);
+    pending.as_u128() + account_value(REWARDS, account)
+}
+
+/// @notice Checkpoint the global reward index and, if given, the rewards of `account`
+fn _update_reward(account: Option<Key>) {
+    let reward_per_token = _reward_per_token();
+    set_key(REWARD_PER_TOKEN_STORED, reward_per_token);
+    set_key(LAST_UPDATE_TIME, _last_time_reward_applicable());
+    if let Some(account) = account {
+        set_account_value(REWARDS, &account, _earned(&account, reward_per_token));
+        Dict::instance(USER_REWARD_PER_TOKEN_PAID).set_by_key(&account, reward_per_token);
+    }
+}
+
+/// @notice Stream `amount` of the reward token over the next week, only the voter may call
+#[no_mangle]
+pub extern "C" fn notify_reward_amount() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let amount = amount.as_u128();
+    let voter = key(VOTER);
+    require(utils::get_immediate_caller_key() == voter, GaugeError::NotVoter);
+    require(token == key(REWARD_TOKEN), GaugeError::InvalidRewardToken);
+
+    _update_reward(None);
+    token::transfer_from(
+        token,
+        standard(REWARD_STANDARD),
+        voter,
+        utils::get_self_key(),
+        amount,
+    );
+
+    let now = current_block_timestamp_seconds();
+    let period_finish: u64 = get_key(PERIOD_FINISH).unwrap_or_revert();
+    let rate = if now >= period_finish {
+        amount / DURATION as u128
+    } else {
+        let left = (period_finish - now) as u128 * u128_key(REWARD_RATE);
+        (amount + left) / DURATION as u128
+    };
+    set_u128_key(REWARD_RATE, rate);
+    set_key(LAST_UPDATE_TIME, now);
+    set_key(PERIOD_FINISH, now + DURATION);
+    emit(&GaugeEvent::NotifyReward {
+        from: voter,
+        amount,
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn get_reward() {
+    let account = utils::get_immediate_caller_key();
+    _update_reward(Some(account));
+    let reward = account_value(REWARDS, &account);
+    if reward > 0 {
+        set_account_value(REWARDS, &account, 0);
+        token::transfer(
+            key(REWARD_TOKEN),
+            standard(REWARD_STANDARD),
+            account,
+            reward,
+        );
+        emit(&GaugeEvent::ClaimRewards {
+            from: account,
+            amount: reward,
+        });
+    }
+    _kick(&account);
+    runtime::ret(CLValue::from_t(U128::from(reward)).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             STAKING
+//////////////////////////////////////////////////////////////*/
+/// @param token_id veNFT boosting the stake, 0 for none. The first one deposited stays
+///        attached until the whole stake is withdrawn.
+#[no_mangle]
+pub extern "C" fn deposit() {
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let amount = amount.as_u128();
+    require(amount > 0, GaugeError::InvalidAmount);
+    let account = utils::get_immediate_caller_key();
+    _update_reward(Some(account));
+
+    token::transfer_from(
+        key(STAKE),
+        standard(STAKE_STANDARD),
+        account,
+        utils::get_self_key(),
+        amount,
+    );
+    set_u128_key(TOTAL_SUPPLY, u128_key(TOTAL_SUPPLY) + amount);
+    set_account_value(BALANCES, &account, account_value(BALANCES, &account) + amount);
+
+    if token_id > 0 {
+        require(
+            escrow::owner_of(key(VOTING_ESCROW), token_id) == Some(account),
+            GaugeError::NotTokenOwner,
+        );
+        let attached = _token_id(&account);
+        if attached == 0 {
+            Dict::instance(TOKEN_IDS).set_by_key(&account, token_id);
+            voter::attach_token_to_gauge(key(VOTER), token_id, account);
+        } else {
+            require(attached == token_id, GaugeError::TokenMismatch);
+        }
+    }
+
+    _kick(&account);
+    emit(&GaugeEvent::Deposit {
+        from: account,
+        token_id,
+        amount,
+    });
+}
+
+/// @notice Withdrawing the whole stake also detaches the veNFT
+#[no_mangle]
+pub extern "C" fn withdraw() {
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let amount = amount.as_u128();
+    let account = utils::get_immediate_caller_key();
+    _update_reward(Some(account));
+
+    let balance = account_value(BALANCES, &account);
+    require(amount > 0 && amount <= balance, GaugeError::InsufficientBalance);
+    set_u128_key(TOTAL_SUPPLY, u128_key(TOTAL_SUPPLY) - amount);
+    set_account_value(BALANCES, &account, balance - amount);
+    token::transfer(key(STAKE), standard(STAKE_STANDARD), account, amount);
+
+    let token_id = _token_id(&account);
+    if amount == balance && token_id > 0 {
+        Dict::instance(TOKEN_IDS).set_by_key(&account, 0u64);
+        voter::detach_token_from_gauge(key(VOTER), token_id, account);
+    }
+
+    _kick(&account);
+    emit(&GaugeEvent::Withdraw {
+        from: account,
+        token_id,
+        amount,
+    });
+}
+
+////////////////////////////////////////////////////////////////
+//                             VIEWS
+//////////////////////////////////////////////////////////////*/
+#[no_mangle]
+pub extern "C" fn balance_of() {
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    let balance = account_value(BALANCES, &account);
+    runtime::ret(CLValue::from_t(U128::from(balance)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn total_supply() {
+    runtime::ret(CLValue::from_t(U128::from(u128_key(TOTAL_SUPPLY))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn derived_balance() {
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    let derived = account_value(DERIVED_BALANCES, &account);
+    runtime::ret(CLValue::from_t(U128::from(derived)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn derived_supply() {
+    runtime::ret(CLValue::from_t(U128::from(u128_key(DERIVED_SUPPLY))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn token_ids() {
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    runtime::ret(CLValue::from_t(U256::from(_token_id(&account))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn earned() {
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    let earned = _earned(&account, _reward_per_token());
+    runtime::ret(CLValue::from_t(U128::from(earned)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn reward_per_token() {
+    runtime::ret(CLValue::from_t(_reward_per_token()).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn reward_rate() {
+    runtime::ret(CLValue::from_t(U128::from(u128_key(REWARD_RATE))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn period_finish() {
+    let period_finish: u64 = get_key(PERIOD_FINISH).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(period_finish).unwrap_or_revert());
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit",
+        vec![
+            Parameter::new(ARG_AMOUNT, U128::cl_type()),
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw",
+        vec![Parameter::new(ARG_AMOUNT, U128::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_reward",
+        vec![],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "notify_reward_amount",
+        vec![
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_AMOUNT, U128::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "kick",
+        vec![Parameter::new(ARG_ACCOUNT, Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for name in ["balance_of", "derived_balance", "earned"].iter() {
+        entry_points.add_entry_point(EntryPoint::new(
+            *name,
+            vec![Parameter::new(ARG_ACCOUNT, Key::cl_type())],
+            U128::cl_type(),
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    for name in ["total_supply", "derived_supply", "reward_rate"].iter() {
+        entry_points.add_entry_point(EntryPoint::new(
+            *name,
+            vec![],
+            U128::cl_type(),
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "token_ids",
+        vec![Parameter::new(ARG_ACCOUNT, Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "reward_per_token",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "period_finish",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
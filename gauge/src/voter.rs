@@ -0,0 +1,31 @@
+//! Calls into the voter, which forwards veNFT attachments to the escrow
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+
+fn package(voter: Key) -> ContractPackageHash {
+    ContractPackageHash::new(voter.into_hash().unwrap_or_revert())
+}
+
+pub fn attach_token_to_gauge(voter: Key, token_id: u64, account: Key) {
+    let _: () = runtime::call_versioned_contract(
+        package(voter),
+        None,
+        "attach_token_to_gauge",
+        runtime_args! {
+            "token_id" => U256::from(token_id),
+            "account" => account
+        },
+    );
+}
+
+pub fn detach_token_from_gauge(voter: Key, token_id: u64, account: Key) {
+    let _: () = runtime::call_versioned_contract(
+        package(voter),
+        None,
+        "detach_token_from_gauge",
+        runtime_args! {
+            "token_id" => U256::from(token_id),
+            "account" => account
+        },
+    );
+}
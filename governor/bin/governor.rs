@@ -0,0 +1,119 @@
+#![no_main]
+#![no_std]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, string::String};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, ContractHash, ContractPackageHash, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U128,
+};
+use governor::governor::{
+    init, ARG_PROPOSAL_THRESHOLD, ARG_QUORUM_BPS, ARG_TIMELOCK_DELAY, ARG_VOTING_DELAY,
+    ARG_VOTING_ESCROW, ARG_VOTING_PERIOD,
+};
+
+#[no_mangle]
+fn constructor() {
+    let voting_escrow: Key = runtime::get_named_arg(ARG_VOTING_ESCROW);
+    let voting_delay: u64 = runtime::get_named_arg(ARG_VOTING_DELAY);
+    let voting_period: u64 = runtime::get_named_arg(ARG_VOTING_PERIOD);
+    let proposal_threshold: U128 = runtime::get_named_arg(ARG_PROPOSAL_THRESHOLD);
+    let quorum_bps: u64 = runtime::get_named_arg(ARG_QUORUM_BPS);
+    let timelock_delay: u64 = runtime::get_named_arg(ARG_TIMELOCK_DELAY);
+    init(
+        voting_escrow,
+        voting_delay,
+        voting_period,
+        proposal_threshold.as_u128(),
+        quorum_bps,
+        timelock_delay,
+    );
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    // Package hash of the escrow, durations in seconds
+    let voting_escrow: Key = runtime::get_named_arg(ARG_VOTING_ESCROW);
+    let voting_delay: u64 = runtime::get_named_arg(ARG_VOTING_DELAY);
+    let voting_period: u64 = runtime::get_named_arg(ARG_VOTING_PERIOD);
+    let proposal_threshold: U128 = runtime::get_named_arg(ARG_PROPOSAL_THRESHOLD);
+    let quorum_bps: u64 = runtime::get_named_arg(ARG_QUORUM_BPS);
+    let timelock_delay: u64 = runtime::get_named_arg(ARG_TIMELOCK_DELAY);
+
+    let (contract_hash, _) = storage::new_contract(
+        get_entry_points(),
+        None,
+        Some(format!("{}_contract_package_hash", contract_name)),
+        Some(format!("{}_contract_package_access", contract_name)),
+    );
+
+    let package_hash: ContractPackageHash = ContractPackageHash::new(
+        runtime::get_key(&format!("{}_contract_package_hash", contract_name))
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
+
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let _: () = runtime::call_contract(
+        contract_hash,
+        "constructor",
+        runtime_args! {
+            ARG_VOTING_ESCROW => voting_escrow,
+            ARG_VOTING_DELAY => voting_delay,
+            ARG_VOTING_PERIOD => voting_period,
+            ARG_PROPOSAL_THRESHOLD => proposal_threshold,
+            ARG_QUORUM_BPS => quorum_bps,
+            ARG_TIMELOCK_DELAY => timelock_delay
+        },
+    );
+
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    put_contract_hash_keys(&contract_name, contract_hash);
+}
+
+fn put_contract_hash_keys(contract_name: &str, contract_hash: ContractHash) {
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = governor::governor::get_entry_points();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new(ARG_VOTING_ESCROW, Key::cl_type()),
+            Parameter::new(ARG_VOTING_DELAY, u64::cl_type()),
+            Parameter::new(ARG_VOTING_PERIOD, u64::cl_type()),
+            Parameter::new(ARG_PROPOSAL_THRESHOLD, U128::cl_type()),
+            Parameter::new(ARG_QUORUM_BPS, u64::cl_type()),
+            Parameter::new(ARG_TIMELOCK_DELAY, u64::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
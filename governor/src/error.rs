@@ -0,0 +1,25 @@
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum GovernorError {
+    NotGovernance = 1,
+    BelowProposalThreshold = 2,
+    InvalidCalldata = 3,
+    UnknownProposal = 4,
+    VotingClosed = 5,
+    AlreadyVoted = 6,
+    InvalidVoteType = 7,
+    NotSucceeded = 8,
+    NotQueued = 9,
+    TimelockNotReady = 10,
+    NotProposer = 11,
+    ProposalFinalized = 12,
+    InvalidSetting = 13,
+}
+
+impl From<GovernorError> for ApiError {
+    fn from(e: GovernorError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
@@ -0,0 +1,33 @@
+//! Calls into the voting escrow, addressed by its package hash so escrow upgrades are followed
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128};
+
+fn package(escrow: Key) -> ContractPackageHash {
+    ContractPackageHash::new(escrow.into_hash().unwrap_or_revert())
+}
+
+/// @notice Voting power delegated to `account` at `timestamp`
+pub fn get_past_votes(escrow: Key, account: Key, timestamp: u64) -> u128 {
+    let votes: U128 = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "get_past_votes",
+        runtime_args! {
+            "address" => account,
+            "timestamp" => timestamp
+        },
+    );
+    votes.as_u128()
+}
+
+pub fn get_past_total_supply(escrow: Key, timestamp: u64) -> u128 {
+    let supply: U128 = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "get_past_total_supply",
+        runtime_args! {
+            "timestamp" => timestamp
+        },
+    );
+    supply.as_u128()
+}
@@ -0,0 +1,78 @@
+use alloc::{collections::BTreeMap, string::ToString};
+use casper_contract::contract_api::storage;
+use casper_types::{Key, URef};
+
+use crate::utils;
+
+pub enum GovernorEvent {
+    ProposalCreated {
+        proposal_id: u64,
+        proposer: Key,
+        target: Key,
+        snapshot: u64,
+        vote_end: u64,
+    },
+    VoteCast {
+        voter: Key,
+        proposal_id: u64,
+        support: u8,
+        weight: u128,
+    },
+    ProposalQueued {
+        proposal_id: u64,
+        eta: u64,
+    },
+    ProposalExecuted {
+        proposal_id: u64,
+    },
+    ProposalCanceled {
+        proposal_id: u64,
+    },
+}
+
+pub fn emit(event: &GovernorEvent) {
+    let mut param = BTreeMap::new();
+    param.insert("contract_package_hash", utils::get_self_key().to_string());
+    match event {
+        GovernorEvent::ProposalCreated {
+            proposal_id,
+            proposer,
+            target,
+            snapshot,
+            vote_end,
+        } => {
+            param.insert("event_type", "governor_proposal_created".to_string());
+            param.insert("proposal_id", proposal_id.to_string());
+            param.insert("proposer", proposer.to_string());
+            param.insert("target", target.to_string());
+            param.insert("snapshot", snapshot.to_string());
+            param.insert("vote_end", vote_end.to_string());
+        }
+        GovernorEvent::VoteCast {
+            voter,
+            proposal_id,
+            support,
+            weight,
+        } => {
+            param.insert("event_type", "governor_vote_cast".to_string());
+            param.insert("voter", voter.to_string());
+            param.insert("proposal_id", proposal_id.to_string());
+            param.insert("support", support.to_string());
+            param.insert("weight", weight.to_string());
+        }
+        GovernorEvent::ProposalQueued { proposal_id, eta } => {
+            param.insert("event_type", "governor_proposal_queued".to_string());
+            param.insert("proposal_id", proposal_id.to_string());
+            param.insert("eta", eta.to_string());
+        }
+        GovernorEvent::ProposalExecuted { proposal_id } => {
+            param.insert("event_type", "governor_proposal_executed".to_string());
+            param.insert("proposal_id", proposal_id.to_string());
+        }
+        GovernorEvent::ProposalCanceled { proposal_id } => {
+            param.insert("event_type", "governor_proposal_canceled".to_string());
+            param.insert("proposal_id", proposal_id.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
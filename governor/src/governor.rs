@@ -0,0 +1,508 @@
+use alloc::string::{String, ToString};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    CLType, CLTyped, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Key, Parameter, RuntimeArgs, U128,
+};
+
+use crate::dict::{get_key, key_and_value_to_str, set_key, Dict};
+use crate::error::GovernorError;
+use crate::escrow;
+use crate::event::{emit, GovernorEvent};
+use crate::proposal::{Proposal, ProposalState, VOTE_ABSTAIN, VOTE_AGAINST, VOTE_FOR};
+use crate::utils::{self, current_block_timestamp_seconds, require};
+
+pub const VOTING_ESCROW: &str = "voting_escrow";
+pub const VOTING_DELAY: &str = "voting_delay";
+pub const VOTING_PERIOD: &str = "voting_period";
+pub const PROPOSAL_THRESHOLD: &str = "proposal_threshold";
+pub const QUORUM_BPS: &str = "quorum_bps";
+pub const TIMELOCK_DELAY: &str = "timelock_delay";
+pub const PROPOSAL_COUNT: &str = "proposal_count";
+pub const PROPOSALS: &str = "proposals";
+pub const RECEIPTS: &str = "receipts";
+
+pub const ARG_VOTING_ESCROW: &str = "voting_escrow";
+pub const ARG_VOTING_DELAY: &str = "voting_delay";
+pub const ARG_VOTING_PERIOD: &str = "voting_period";
+pub const ARG_PROPOSAL_THRESHOLD: &str = "proposal_threshold";
+pub const ARG_QUORUM_BPS: &str = "quorum_bps";
+pub const ARG_TIMELOCK_DELAY: &str = "timelock_delay";
+pub const ARG_TARGET: &str = "target";
+pub const ARG_ENTRY_POINT: &str = "entry_point";
+pub const ARG_ARGS: &str = "args";
+pub const ARG_DESCRIPTION: &str = "description";
+pub const ARG_PROPOSAL_ID: &str = "proposal_id";
+pub const ARG_SUPPORT: &str = "support";
+pub const ARG_ACCOUNT: &str = "account";
+pub const ARG_TIMESTAMP: &str = "timestamp";
+
+pub const BPS: u128 = 10_000;
+pub const DAY: u64 = 24 * 60 * 60;
+pub const MAX_VOTING_DELAY: u64 = 30 * DAY;
+pub const MAX_VOTING_PERIOD: u64 = 60 * DAY;
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * DAY;
+/// A queued proposal that is not executed within this window expires
+pub const GRACE_PERIOD: u64 = 14 * DAY;
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+/// @param voting_escrow Package hash of the escrow providing `get_past_votes`
+/// @param proposal_threshold Votes a proposer needs at proposal time
+/// @param quorum_bps Share of the past total supply that must vote for or abstain
+pub fn init(
+    voting_escrow: Key,
+    voting_delay: u64,
+    voting_period: u64,
+    proposal_threshold: u128,
+    quorum_bps: u64,
+    timelock_delay: u64,
+) {
+    set_key(VOTING_ESCROW, voting_escrow);
+    _set_voting_delay(voting_delay);
+    _set_voting_period(voting_period);
+    set_key(PROPOSAL_THRESHOLD, U128::from(proposal_threshold));
+    _set_quorum_bps(quorum_bps);
+    _set_timelock_delay(timelock_delay);
+    set_key(PROPOSAL_COUNT, 0u64);
+    Dict::init(PROPOSALS);
+    Dict::init(RECEIPTS);
+}
+
+fn voting_escrow() -> Key {
+    get_key(VOTING_ESCROW).unwrap_or_revert()
+}
+
+fn u64_key(name: &str) -> u64 {
+    get_key(name).unwrap_or_revert()
+}
+
+fn proposal_threshold() -> u128 {
+    let threshold: U128 = get_key(PROPOSAL_THRESHOLD).unwrap_or_revert();
+    threshold.as_u128()
+}
+
+fn _proposal(proposal_id: u64) -> Proposal {
+    Dict::instance(PROPOSALS)
+        .get(&proposal_id.to_string())
+        .unwrap_or_revert_with(GovernorError::UnknownProposal)
+}
+
+fn set_proposal(proposal_id: u64, proposal: Proposal) {
+    Dict::instance(PROPOSALS).set(&proposal_id.to_string(), proposal);
+}
+
+fn _receipt(proposal_id: u64, account: &Key) -> Option<(u8, U128)> {
+    Dict::instance(RECEIPTS).get(&key_and_value_to_str(account, &proposal_id))
+}
+
+fn _quorum(timestamp: u64) -> u128 {
+    let supply = escrow::get_past_total_supply(voting_escrow(), timestamp);
+    supply * u128::from(u64_key(QUORUM_BPS)) / BPS
+}
+
+fn _state(proposal: &Proposal) -> ProposalState {
+    if proposal.executed {
+        return ProposalState::Executed;
+    }
+    if proposal.canceled {
+        return ProposalState::Canceled;
+    }
+    let now = current_block_timestamp_seconds();
+    // Checkpoints of the snapshot second can still change, votes open once it has passed
+    if now <= proposal.snapshot {
+        return ProposalState::Pending;
+    }
+    if now <= proposal.vote_end {
+        return ProposalState::Active;
+    }
+    let quorum_reached = proposal.for_votes + proposal.abstain_votes >= _quorum(proposal.snapshot);
+    if !quorum_reached || proposal.for_votes <= proposal.against_votes {
+        return ProposalState::Defeated;
+    }
+    if proposal.eta == 0 {
+        ProposalState::Succeeded
+    } else if now >= proposal.eta + GRACE_PERIOD {
+        ProposalState::Expired
+    } else {
+        ProposalState::Queued
+    }
+}
+
+fn decode_args(args: &Bytes) -> RuntimeArgs {
+    bytesrepr::deserialize(args.to_vec()).unwrap_or_revert_with(GovernorError::InvalidCalldata)
+}
+
+////////////////////////////////////////////////////////////////
+//                             PROPOSALS
+//////////////////////////////////////////////////////////////*/
+/// @notice Put a call of `entry_point` on `target` with `args` to the vote
+/// @param target Package hash of the called contract, the entry point must return unit
+/// @param args Serialized `RuntimeArgs` of the call
+/// @return The proposal id
+#[no_mangle]
+pub extern "C" fn propose() {
+    let target: Key = runtime::get_named_arg(ARG_TARGET);
+    let entry_point: String = runtime::get_named_arg(ARG_ENTRY_POINT);
+    let args: Bytes = runtime::get_named_arg(ARG_ARGS);
+    let description: String = runtime::get_named_arg(ARG_DESCRIPTION);
+    require(
+        target.into_hash().is_some() && !entry_point.is_empty(),
+        GovernorError::InvalidCalldata,
+    );
+    require(description.len() <= MAX_DESCRIPTION_LENGTH, GovernorError::InvalidCalldata);
+    decode_args(&args);
+
+    let proposer = utils::get_immediate_caller_key();
+    let now = current_block_timestamp_seconds();
+    let votes = escrow::get_past_votes(voting_escrow(), proposer, now.saturating_sub(1));
+    require(votes >= proposal_threshold(), GovernorError::BelowProposalThreshold);
+
+    let proposal_id = u64_key(PROPOSAL_COUNT) + 1;
+    set_key(PROPOSAL_COUNT, proposal_id);
+    let snapshot = now + u64_key(VOTING_DELAY);
+    let vote_end = snapshot + u64_key(VOTING_PERIOD);
+    set_proposal(
+        proposal_id,
+        Proposal {
+            proposer,
+            target,
+            entry_point,
+            args,
+            description,
+            snapshot,
+            vote_end,
+            eta: 0,
+            for_votes: 0,
+            against_votes: 0,
+            abstain_votes: 0,
+            executed: false,
+            canceled: false,
+        },
+    );
+    emit(&GovernorEvent::ProposalCreated {
+        proposal_id,
+        proposer,
+        target,
+        snapshot,
+        vote_end,
+    });
+    runtime::ret(CLValue::from_t(proposal_id).unwrap_or_revert());
+}
+
+/// @param support 0 against, 1 for, 2 abstain
+/// @return The weight of the vote, the caller's escrow votes at the proposal snapshot
+#[no_mangle]
+pub extern "C" fn cast_vote() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let support: u8 = runtime::get_named_arg(ARG_SUPPORT);
+    let mut proposal = _proposal(proposal_id);
+    require(_state(&proposal) == ProposalState::Active, GovernorError::VotingClosed);
+    let voter = utils::get_immediate_caller_key();
+    require(_receipt(proposal_id, &voter).is_none(), GovernorError::AlreadyVoted);
+
+    let weight = escrow::get_past_votes(voting_escrow(), voter, proposal.snapshot);
+    match support {
+        VOTE_AGAINST => proposal.against_votes += weight,
+        VOTE_FOR => proposal.for_votes += weight,
+        VOTE_ABSTAIN => proposal.abstain_votes += weight,
+        _ => runtime::revert(GovernorError::InvalidVoteType),
+    }
+    set_proposal(proposal_id, proposal);
+    Dict::instance(RECEIPTS).set(
+        &key_and_value_to_str(&voter, &proposal_id),
+        (support, U128::from(weight)),
+    );
+    emit(&GovernorEvent::VoteCast {
+        voter,
+        proposal_id,
+        support,
+        weight,
+    });
+    runtime::ret(CLValue::from_t(U128::from(weight)).unwrap_or_revert());
+}
+
+/// @notice Start the timelock of a succeeded proposal
+#[no_mangle]
+pub extern "C" fn queue() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let mut proposal = _proposal(proposal_id);
+    require(_state(&proposal) == ProposalState::Succeeded, GovernorError::NotSucceeded);
+    let eta = current_block_timestamp_seconds() + u64_key(TIMELOCK_DELAY);
+    proposal.eta = eta;
+    set_proposal(proposal_id, proposal);
+    emit(&GovernorEvent::ProposalQueued { proposal_id, eta });
+}
+
+/// @notice Perform the call of a queued proposal once its timelock passed, callable by anyone
+#[no_mangle]
+pub extern "C" fn execute() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let mut proposal = _proposal(proposal_id);
+    require(_state(&proposal) == ProposalState::Queued, GovernorError::NotQueued);
+    require(current_block_timestamp_seconds() >= proposal.eta, GovernorError::TimelockNotReady);
+    proposal.executed = true;
+    set_proposal(proposal_id, proposal.clone());
+
+    let target = ContractPackageHash::new(proposal.target.into_hash().unwrap_or_revert());
+    let _: () = runtime::call_versioned_contract(
+        target,
+        None,
+        &proposal.entry_point,
+        decode_args(&proposal.args),
+    );
+    emit(&GovernorEvent::ProposalExecuted { proposal_id });
+}
+
+/// @notice The proposer may withdraw a proposal until it is executed
+#[no_mangle]
+pub extern "C" fn cancel() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let mut proposal = _proposal(proposal_id);
+    require(utils::get_immediate_caller_key() == proposal.proposer, GovernorError::NotProposer);
+    let state = _state(&proposal);
+    require(
+        state != ProposalState::Executed
+            && state != ProposalState::Canceled
+            && state != ProposalState::Expired,
+        GovernorError::ProposalFinalized,
+    );
+    proposal.canceled = true;
+    set_proposal(proposal_id, proposal);
+    emit(&GovernorEvent::ProposalCanceled { proposal_id });
+}
+
+////////////////////////////////////////////////////////////////
+//                             SETTINGS
+//////////////////////////////////////////////////////////////*/
+/// @notice Settings only change through an executed proposal targeting this contract
+fn only_governance() {
+    require(
+        utils::get_immediate_caller_key() == utils::get_self_key(),
+        GovernorError::NotGovernance,
+    );
+}
+
+fn _set_voting_delay(delay: u64) {
+    require(delay <= MAX_VOTING_DELAY, GovernorError::InvalidSetting);
+    set_key(VOTING_DELAY, delay);
+}
+
+fn _set_voting_period(period: u64) {
+    require(period > 0 && period <= MAX_VOTING_PERIOD, GovernorError::InvalidSetting);
+    set_key(VOTING_PERIOD, period);
+}
+
+fn _set_quorum_bps(quorum_bps: u64) {
+    require(quorum_bps > 0 && u128::from(quorum_bps) <= BPS, GovernorError::InvalidSetting);
+    set_key(QUORUM_BPS, quorum_bps);
+}
+
+fn _set_timelock_delay(delay: u64) {
+    require(delay <= MAX_TIMELOCK_DELAY, GovernorError::InvalidSetting);
+    set_key(TIMELOCK_DELAY, delay);
+}
+
+#[no_mangle]
+pub extern "C" fn set_voting_delay() {
+    let delay: u64 = runtime::get_named_arg(ARG_VOTING_DELAY);
+    only_governance();
+    _set_voting_delay(delay);
+}
+
+#[no_mangle]
+pub extern "C" fn set_voting_period() {
+    let period: u64 = runtime::get_named_arg(ARG_VOTING_PERIOD);
+    only_governance();
+    _set_voting_period(period);
+}
+
+#[no_mangle]
+pub extern "C" fn set_proposal_threshold() {
+    let threshold: U128 = runtime::get_named_arg(ARG_PROPOSAL_THRESHOLD);
+    only_governance();
+    set_key(PROPOSAL_THRESHOLD, threshold);
+}
+
+#[no_mangle]
+pub extern "C" fn set_quorum_bps() {
+    let quorum_bps: u64 = runtime::get_named_arg(ARG_QUORUM_BPS);
+    only_governance();
+    _set_quorum_bps(quorum_bps);
+}
+
+#[no_mangle]
+pub extern "C" fn set_timelock_delay() {
+    let delay: u64 = runtime::get_named_arg(ARG_TIMELOCK_DELAY);
+    only_governance();
+    _set_timelock_delay(delay);
+}
+
+////////////////////////////////////////////////////////////////
+//                             VIEWS
+//////////////////////////////////////////////////////////////*/
+#[no_mangle]
+pub extern "C" fn state() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let state = _state(&_proposal(proposal_id)) as u8;
+    runtime::ret(CLValue::from_t(state).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn proposal() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    runtime::ret(CLValue::from_t(_proposal(proposal_id)).unwrap_or_revert());
+}
+
+/// @return (against, for, abstain) votes
+#[no_mangle]
+pub extern "C" fn proposal_votes() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let proposal = _proposal(proposal_id);
+    let votes = (
+        U128::from(proposal.against_votes),
+        U128::from(proposal.for_votes),
+        U128::from(proposal.abstain_votes),
+    );
+    runtime::ret(CLValue::from_t(votes).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn has_voted() {
+    let proposal_id: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    let voted = _receipt(proposal_id, &account).is_some();
+    runtime::ret(CLValue::from_t(voted).unwrap_or_revert());
+}
+
+/// @notice Votes needed for a proposal snapshotted at `timestamp` to pass
+#[no_mangle]
+pub extern "C" fn quorum() {
+    let timestamp: u64 = runtime::get_named_arg(ARG_TIMESTAMP);
+    runtime::ret(CLValue::from_t(U128::from(_quorum(timestamp))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_proposal_threshold() {
+    runtime::ret(CLValue::from_t(U128::from(proposal_threshold())).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn proposal_count() {
+    runtime::ret(CLValue::from_t(u64_key(PROPOSAL_COUNT)).unwrap_or_revert());
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose",
+        vec![
+            Parameter::new(ARG_TARGET, Key::cl_type()),
+            Parameter::new(ARG_ENTRY_POINT, String::cl_type()),
+            Parameter::new(ARG_ARGS, Bytes::cl_type()),
+            Parameter::new(ARG_DESCRIPTION, String::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "cast_vote",
+        vec![
+            Parameter::new(ARG_PROPOSAL_ID, CLType::U64),
+            Parameter::new(ARG_SUPPORT, CLType::U8),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for name in ["queue", "execute", "cancel"].iter() {
+        entry_points.add_entry_point(EntryPoint::new(
+            *name,
+            vec![Parameter::new(ARG_PROPOSAL_ID, CLType::U64)],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    let settings = [
+        ("set_voting_delay", ARG_VOTING_DELAY, CLType::U64),
+        ("set_voting_period", ARG_VOTING_PERIOD, CLType::U64),
+        ("set_proposal_threshold", ARG_PROPOSAL_THRESHOLD, U128::cl_type()),
+        ("set_quorum_bps", ARG_QUORUM_BPS, CLType::U64),
+        ("set_timelock_delay", ARG_TIMELOCK_DELAY, CLType::U64),
+    ];
+    for (name, arg, cl_type) in settings.iter() {
+        entry_points.add_entry_point(EntryPoint::new(
+            *name,
+            vec![Parameter::new(*arg, cl_type.clone())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "state",
+        vec![Parameter::new(ARG_PROPOSAL_ID, CLType::U64)],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "proposal",
+        vec![Parameter::new(ARG_PROPOSAL_ID, CLType::U64)],
+        Proposal::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "proposal_votes",
+        vec![Parameter::new(ARG_PROPOSAL_ID, CLType::U64)],
+        <(U128, U128, U128)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "has_voted",
+        vec![
+            Parameter::new(ARG_PROPOSAL_ID, CLType::U64),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "quorum",
+        vec![Parameter::new(ARG_TIMESTAMP, CLType::U64)],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_proposal_threshold",
+        vec![],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "proposal_count",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
@@ -0,0 +1,123 @@
+use alloc::{string::String, vec::Vec};
+use casper_types::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    CLType, CLTyped, Key, U128,
+};
+
+/// Lifecycle of a proposal, as returned by `state`
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProposalState {
+    Pending = 0,
+    Active = 1,
+    Canceled = 2,
+    Defeated = 3,
+    Succeeded = 4,
+    Queued = 5,
+    Expired = 6,
+    Executed = 7,
+}
+
+pub const VOTE_AGAINST: u8 = 0;
+pub const VOTE_FOR: u8 = 1;
+pub const VOTE_ABSTAIN: u8 = 2;
+
+/// A single contract call put to the vote
+#[derive(Clone)]
+pub struct Proposal {
+    pub proposer: Key,
+    /// Package hash of the called contract
+    pub target: Key,
+    pub entry_point: String,
+    /// Serialized `RuntimeArgs` of the call
+    pub args: Bytes,
+    pub description: String,
+    /// Votes are weighted by the escrow voting power at this timestamp
+    pub snapshot: u64,
+    pub vote_end: u64,
+    /// Earliest execution time once queued, 0 before
+    pub eta: u64,
+    pub for_votes: u128,
+    pub against_votes: u128,
+    pub abstain_votes: u128,
+    pub executed: bool,
+    pub canceled: bool,
+}
+
+impl ToBytes for Proposal {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend(self.proposer.to_bytes()?);
+        result.extend(self.target.to_bytes()?);
+        result.extend(self.entry_point.to_bytes()?);
+        result.extend(self.args.to_bytes()?);
+        result.extend(self.description.to_bytes()?);
+        result.extend(self.snapshot.to_bytes()?);
+        result.extend(self.vote_end.to_bytes()?);
+        result.extend(self.eta.to_bytes()?);
+        result.extend(U128::from(self.for_votes).to_bytes()?);
+        result.extend(U128::from(self.against_votes).to_bytes()?);
+        result.extend(U128::from(self.abstain_votes).to_bytes()?);
+        result.extend(self.executed.to_bytes()?);
+        result.extend(self.canceled.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.proposer.serialized_length()
+            + self.target.serialized_length()
+            + self.entry_point.serialized_length()
+            + self.args.serialized_length()
+            + self.description.serialized_length()
+            + self.snapshot.serialized_length()
+            + self.vote_end.serialized_length()
+            + self.eta.serialized_length()
+            + U128::from(self.for_votes).serialized_length()
+            + U128::from(self.against_votes).serialized_length()
+            + U128::from(self.abstain_votes).serialized_length()
+            + self.executed.serialized_length()
+            + self.canceled.serialized_length()
+    }
+}
+
+impl FromBytes for Proposal {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (proposer, remainder) = Key::from_bytes(bytes)?;
+        let (target, remainder) = Key::from_bytes(remainder)?;
+        let (entry_point, remainder) = String::from_bytes(remainder)?;
+        let (args, remainder) = Bytes::from_bytes(remainder)?;
+        let (description, remainder) = String::from_bytes(remainder)?;
+        let (snapshot, remainder) = u64::from_bytes(remainder)?;
+        let (vote_end, remainder) = u64::from_bytes(remainder)?;
+        let (eta, remainder) = u64::from_bytes(remainder)?;
+        let (for_votes, remainder) = U128::from_bytes(remainder)?;
+        let (against_votes, remainder) = U128::from_bytes(remainder)?;
+        let (abstain_votes, remainder) = U128::from_bytes(remainder)?;
+        let (executed, remainder) = bool::from_bytes(remainder)?;
+        let (canceled, remainder) = bool::from_bytes(remainder)?;
+        Ok((
+            Proposal {
+                proposer,
+                target,
+                entry_point,
+                args,
+                description,
+                snapshot,
+                vote_end,
+                eta,
+                for_votes: for_votes.as_u128(),
+                against_votes: against_votes.as_u128(),
+                abstain_votes: abstain_votes.as_u128(),
+                executed,
+                canceled,
+            },
+            remainder,
+        ))
+    }
+}
+
+impl CLTyped for Proposal {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
@@ -0,0 +1,13 @@
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum MinterError {
+    InvalidEmission = 1,
+}
+
+impl From<MinterError> for ApiError {
+    fn from(e: MinterError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
@@ -0,0 +1,37 @@
+use alloc::{collections::BTreeMap, string::ToString};
+use casper_contract::contract_api::storage;
+use casper_types::{Key, URef};
+
+use crate::utils;
+
+pub enum MinterEvent {
+    Mint {
+        sender: Key,
+        weekly: u128,
+        circulating_supply: u128,
+        circulating_emission: u128,
+        rebase: u128,
+    },
+}
+
+pub fn emit(event: &MinterEvent) {
+    let mut param = BTreeMap::new();
+    param.insert("contract_package_hash", utils::get_self_key().to_string());
+    match event {
+        MinterEvent::Mint {
+            sender,
+            weekly,
+            circulating_supply,
+            circulating_emission,
+            rebase,
+        } => {
+            param.insert("event_type", "minter_mint".to_string());
+            param.insert("sender", sender.to_string());
+            param.insert("weekly", weekly.to_string());
+            param.insert("circulating_supply", circulating_supply.to_string());
+            param.insert("circulating_emission", circulating_emission.to_string());
+            param.insert("rebase", rebase.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
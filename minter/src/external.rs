@@ -0,0 +1,52 @@
+//! Calls into the escrow, rewards distributor and voter (by package hash, so upgrades of
+//! those are followed). The token is called through `common::token`.
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128};
+
+fn package(key: Key) -> ContractPackageHash {
+    ContractPackageHash::new(key.into_hash().unwrap_or_revert())
+}
+
+/// @notice Amount of `token` locked in the escrow
+pub fn locked_supply(escrow: Key, token: Key) -> u128 {
+    let supply: U128 = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "asset_supply",
+        runtime_args! {
+            "asset" => token
+        },
+    );
+    supply.as_u128()
+}
+
+/// @notice Voting power of all locks in the escrow right now
+pub fn ve_total_supply(escrow: Key) -> u128 {
+    let supply: U128 = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "ve_total_supply",
+        runtime_args! {},
+    );
+    supply.as_u128()
+}
+
+pub fn checkpoint_token(distributor: Key) {
+    let _: () = runtime::call_versioned_contract(
+        package(distributor),
+        None,
+        "checkpoint_token",
+        runtime_args! {},
+    );
+}
+
+pub fn notify_reward_amount(voter: Key, amount: u128) {
+    let _: () = runtime::call_versioned_contract(
+        package(voter),
+        None,
+        "notify_reward_amount",
+        runtime_args! {
+            "amount" => U128::from(amount)
+        },
+    );
+}
@@ -0,0 +1,10 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+
+pub mod error;
+pub mod event;
+pub mod external;
+pub mod minter;
+
+pub use common::{dict, token, utils};
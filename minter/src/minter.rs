@@ -0,0 +1,200 @@
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, U128, U256,
+};
+
+use crate::dict::{get_key, set_key};
+use crate::error::MinterError;
+use crate::event::{emit, MinterEvent};
+use crate::external;
+use crate::token::{self, TokenStandard};
+use crate::utils::{self, current_block_timestamp_seconds, require, WEEK};
+
+pub const TOKEN: &str = "token";
+pub const VOTING_ESCROW: &str = "voting_escrow";
+pub const REWARDS_DISTRIBUTOR: &str = "rewards_distributor";
+pub const VOTER: &str = "voter";
+pub const WEEKLY: &str = "weekly";
+pub const ACTIVE_PERIOD: &str = "active_period";
+pub const TOKEN_STANDARD: &str = "token_standard";
+
+pub const ARG_TOKEN: &str = "token";
+pub const ARG_VOTING_ESCROW: &str = "voting_escrow";
+pub const ARG_REWARDS_DISTRIBUTOR: &str = "rewards_distributor";
+pub const ARG_VOTER: &str = "voter";
+pub const ARG_WEEKLY: &str = "weekly";
+pub const ARG_MINTED: &str = "minted";
+pub const ARG_TOKEN_STANDARD: &str = "token_standard";
+
+/// Weekly emissions decay by 1%
+pub const EMISSION: u128 = 990;
+/// Emissions never drop below 0.2% of the circulating supply per week
+pub const TAIL_EMISSION: u128 = 2;
+pub const PRECISION: u128 = 1000;
+
+/// @param token Hash of the mintable underlying
+/// @param voting_escrow, rewards_distributor, voter Package hashes of those contracts.
+///        The distributor has to name this contract as its depositor.
+/// @param token_standard Standard of `token`, as the escrow's install argument
+pub fn init(
+    token: Key,
+    voting_escrow: Key,
+    rewards_distributor: Key,
+    voter: Key,
+    weekly: u128,
+    token_standard: u8,
+) {
+    require(weekly > 0, MinterError::InvalidEmission);
+    TokenStandard::require_from_u8(token_standard);
+    set_key(TOKEN, token);
+    set_key(TOKEN_STANDARD, token_standard);
+    set_key(VOTING_ESCROW, voting_escrow);
+    set_key(REWARDS_DISTRIBUTOR, rewards_distributor);
+    set_key(VOTER, voter);
+    set_key(WEEKLY, U128::from(weekly));
+    // The first emission happens at the next week boundary
+    set_key(ACTIVE_PERIOD, current_block_timestamp_seconds() / WEEK * WEEK);
+}
+
+fn key(name: &str) -> Key {
+    get_key(name).unwrap_or_revert()
+}
+
+fn token_standard() -> TokenStandard {
+    TokenStandard::require_from_u8(get_key(TOKEN_STANDARD).unwrap_or_revert())
+}
+
+fn weekly() -> u128 {
+    let weekly: U128 = get_key(WEEKLY).unwrap_or_revert();
+    weekly.as_u128()
+}
+
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    (U256::from(a) * U256::from(b) / U256::from(c)).as_u128()
+}
+
+/// @notice Token supply outside of the escrow
+fn _circulating_supply() -> u128 {
+    let token = key(TOKEN);
+    let locked = external::locked_supply(key(VOTING_ESCROW), token);
+    token::total_supply(token, token_standard()).saturating_sub(locked)
+}
+
+fn _circulating_emission() -> u128 {
+    _circulating_supply() * TAIL_EMISSION / PRECISION
+}
+
+/// @notice Emission for the coming week, the decayed schedule or the tail emission
+fn _weekly_emission() -> u128 {
+    core::cmp::max(weekly() * EMISSION / PRECISION, _circulating_emission())
+}
+
+/// @notice Rebase owed to lockers for `minted` new tokens, proportional to the escrow's
+///         voting power over the circulating supply (capped at `minted`)
+fn _calculate_growth(minted: u128) -> u128 {
+    let circulating = _circulating_supply();
+    if circulating == 0 {
+        return 0;
+    }
+    let ve_supply = external::ve_total_supply(key(VOTING_ESCROW));
+    core::cmp::min(mul_div(minted, ve_supply, circulating), minted)
+}
+
+/// @notice Once per week: mint the emission, send the rebase to the rewards distributor
+///         and the rest to the voter
+/// @return The active period
+#[no_mangle]
+pub extern "C" fn update_period() {
+    let mut period: u64 = get_key(ACTIVE_PERIOD).unwrap_or_revert();
+    let now = current_block_timestamp_seconds();
+    if now >= period + WEEK {
+        period = now / WEEK * WEEK;
+        set_key(ACTIVE_PERIOD, period);
+
+        let circulating_supply = _circulating_supply();
+        let circulating_emission = _circulating_emission();
+        let weekly = core::cmp::max(weekly() * EMISSION / PRECISION, circulating_emission);
+        set_key(WEEKLY, U128::from(weekly));
+
+        let rebase = _calculate_growth(weekly);
+        let required = rebase + weekly;
+        let token = key(TOKEN);
+        let standard = token_standard();
+        let balance = token::balance_of(token, standard, utils::get_self_key());
+        if balance < required {
+            token::mint(token, standard, utils::get_self_key(), required - balance);
+        }
+
+        let distributor = key(REWARDS_DISTRIBUTOR);
+        token::transfer(token, standard, distributor, rebase);
+        external::checkpoint_token(distributor);
+
+        let voter = key(VOTER);
+        token::approve(token, standard, voter, weekly);
+        external::notify_reward_amount(voter, weekly);
+
+        emit(&MinterEvent::Mint {
+            sender: utils::get_immediate_caller_key(),
+            weekly,
+            circulating_supply,
+            circulating_emission,
+            rebase,
+        });
+    }
+    runtime::ret(CLValue::from_t(period).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn weekly_emission() {
+    runtime::ret(CLValue::from_t(U128::from(_weekly_emission())).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn circulating_supply() {
+    runtime::ret(CLValue::from_t(U128::from(_circulating_supply())).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn calculate_growth() {
+    let minted: U128 = runtime::get_named_arg(ARG_MINTED);
+    let growth = _calculate_growth(minted.as_u128());
+    runtime::ret(CLValue::from_t(U128::from(growth)).unwrap_or_revert());
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_period",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "weekly_emission",
+        vec![],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "circulating_supply",
+        vec![],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "calculate_growth",
+        vec![Parameter::new(ARG_MINTED, U128::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
@@ -0,0 +1,97 @@
+#![no_main]
+#![no_std]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, string::String};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, ContractHash, ContractPackageHash, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef,
+};
+use rewards_distributor::distributor::{self, ARG_TOKEN, ARG_TOKEN_STANDARD, ARG_VOTING_ESCROW};
+
+#[no_mangle]
+fn constructor() {
+    let voting_escrow: Key = runtime::get_named_arg(ARG_VOTING_ESCROW);
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let token_standard: u8 = runtime::get_named_arg(ARG_TOKEN_STANDARD);
+    distributor::init(voting_escrow, token, token_standard);
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    // Package hash of the escrow and the reward token, addressed as its standard expects
+    let voting_escrow: Key = runtime::get_named_arg(ARG_VOTING_ESCROW);
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let token_standard: u8 = runtime::get_named_arg(ARG_TOKEN_STANDARD);
+
+    let (contract_hash, _) = storage::new_contract(
+        get_entry_points(),
+        None,
+        Some(format!("{}_contract_package_hash", contract_name)),
+        Some(format!("{}_contract_package_access", contract_name)),
+    );
+
+    let package_hash: ContractPackageHash = ContractPackageHash::new(
+        runtime::get_key(&format!("{}_contract_package_hash", contract_name))
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
+
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let _: () = runtime::call_contract(
+        contract_hash,
+        "constructor",
+        runtime_args! {
+            ARG_VOTING_ESCROW => voting_escrow,
+            ARG_TOKEN => token,
+            ARG_TOKEN_STANDARD => token_standard
+        },
+    );
+
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    put_contract_hash_keys(&contract_name, contract_hash);
+}
+
+fn put_contract_hash_keys(contract_name: &str, contract_hash: ContractHash) {
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = distributor::get_entry_points();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new(ARG_VOTING_ESCROW, Key::cl_type()),
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_TOKEN_STANDARD, u8::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
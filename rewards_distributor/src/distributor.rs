@@ -0,0 +1,513 @@
+use alloc::{string::ToString, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, U128, U256,
+};
+
+use crate::dict::{get_key, set_key, Dict};
+use crate::error::DistributorError;
+use crate::escrow::{self, Point};
+use crate::event::{emit, DistributorEvent};
+use crate::token::{self, TokenStandard};
+use crate::utils::{self, current_block_timestamp_seconds, require, WEEK};
+
+pub const VOTING_ESCROW: &str = "voting_escrow";
+pub const TOKEN: &str = "token";
+pub const DEPOSITOR: &str = "depositor";
+pub const START_TIME: &str = "start_time";
+pub const TIME_CURSOR: &str = "time_cursor";
+pub const LAST_TOKEN_TIME: &str = "last_token_time";
+pub const TOKEN_LAST_BALANCE: &str = "token_last_balance";
+pub const TIME_CURSOR_OF: &str = "time_cursor_of";
+pub const USER_EPOCH_OF: &str = "user_epoch_of";
+pub const TOKENS_PER_WEEK: &str = "tokens_per_week";
+pub const VE_SUPPLY: &str = "ve_supply";
+pub const TOKEN_STANDARD: &str = "token_standard";
+
+pub const ARG_VOTING_ESCROW: &str = "voting_escrow";
+pub const ARG_TOKEN: &str = "token";
+pub const ARG_TOKEN_ID: &str = "token_id";
+pub const ARG_TOKEN_IDS: &str = "token_ids";
+pub const ARG_TIMESTAMP: &str = "timestamp";
+pub const ARG_COMPOUND: &str = "compound";
+pub const ARG_DEPOSITOR: &str = "depositor";
+pub const ARG_WEEK: &str = "week";
+pub const ARG_TOKEN_STANDARD: &str = "token_standard";
+
+pub const MAX_CLAIM_BATCH: usize = 50;
+
+pub fn init(voting_escrow: Key, token: Key, token_standard: u8) {
+    TokenStandard::require_from_u8(token_standard);
+    let t = current_block_timestamp_seconds() / WEEK * WEEK;
+    set_key(VOTING_ESCROW, voting_escrow);
+    set_key(TOKEN, token);
+    set_key(TOKEN_STANDARD, token_standard);
+    set_key(DEPOSITOR, utils::get_immediate_caller_key());
+    set_key(START_TIME, t);
+    set_key(LAST_TOKEN_TIME, t);
+    set_key(TIME_CURSOR, t);
+    set_key(TOKEN_LAST_BALANCE, U128::zero());
+    Dict::init(TIME_CURSOR_OF);
+    Dict::init(USER_EPOCH_OF);
+    Dict::init(TOKENS_PER_WEEK);
+    Dict::init(VE_SUPPLY);
+}
+
+fn voting_escrow() -> Key {
+    get_key(VOTING_ESCROW).unwrap_or_revert()
+}
+
+fn reward_token() -> Key {
+    get_key(TOKEN).unwrap_or_revert()
+}
+
+fn token_standard() -> TokenStandard {
+    TokenStandard::require_from_u8(get_key(TOKEN_STANDARD).unwrap_or_revert())
+}
+
+fn only_depositor() {
+    let depositor: Key = get_key(DEPOSITOR).unwrap_or_revert();
+    require(
+        utils::get_immediate_caller_key() == depositor,
+        DistributorError::NotDepositor,
+    );
+}
+
+fn week_value(dict_name: &str, week: u64) -> u128 {
+    let value: U128 = Dict::instance(dict_name)
+        .get(&week.to_string())
+        .unwrap_or_default();
+    value.as_u128()
+}
+
+fn set_week_value(dict_name: &str, week: u64, value: u128) {
+    Dict::instance(dict_name).set(&week.to_string(), U128::from(value));
+}
+
+fn token_last_balance() -> u128 {
+    let balance: U128 = get_key(TOKEN_LAST_BALANCE).unwrap_or_revert();
+    balance.as_u128()
+}
+
+/// `a * b / c` without overflowing the intermediate product
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    (U256::from(a) * U256::from(b) / U256::from(c)).as_u128()
+}
+
+fn balance_at(point: &Point, t: u64) -> u128 {
+    let dt = t as i128 - point.ts as i128;
+    let bias = point.bias - point.slope * dt;
+    if bias > 0 {
+        bias as u128
+    } else {
+        0
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                             TOKEN CHECKPOINTS
+//////////////////////////////////////////////////////////////*/
+/// @notice Spread the tokens received since the last checkpoint over the weeks
+///         that passed, proportionally to the time spent in each of them
+fn _checkpoint_token() {
+    let token_balance = token::balance_of(reward_token(), token_standard(), utils::get_self_key());
+    let to_distribute = token_balance.saturating_sub(token_last_balance());
+    set_key(TOKEN_LAST_BALANCE, U128::from(token_balance));
+
+    let now = current_block_timestamp_seconds();
+    let mut t: u64 = get_key(LAST_TOKEN_TIME).unwrap_or_revert();
+    let since_last = now - t;
+    set_key(LAST_TOKEN_TIME, now);
+    let mut this_week = t / WEEK * WEEK;
+
+    for _ in 0..20 {
+        let next_week = this_week + WEEK;
+        let current = week_value(TOKENS_PER_WEEK, this_week);
+        if now < next_week {
+            let share = if since_last == 0 && now == t {
+                to_distribute
+            } else {
+                mul_div(to_distribute, (now - t) as u128, since_last as u128)
+            };
+            set_week_value(TOKENS_PER_WEEK, this_week, current + share);
+            break;
+        }
+        let share = if since_last == 0 && next_week == t {
+            to_distribute
+        } else {
+            mul_div(to_distribute, (next_week - t) as u128, since_last as u128)
+        };
+        set_week_value(TOKENS_PER_WEEK, this_week, current + share);
+        t = next_week;
+        this_week = next_week;
+    }
+
+    emit(&DistributorEvent::CheckpointToken {
+        time: now,
+        tokens: to_distribute,
+    });
+}
+
+/// @notice Distribute the tokens received since the last call, only the depositor may call
+#[no_mangle]
+pub extern "C" fn checkpoint_token() {
+    only_depositor();
+    _checkpoint_token();
+}
+
+#[no_mangle]
+pub extern "C" fn set_depositor() {
+    let depositor: Key = runtime::get_named_arg(ARG_DEPOSITOR);
+    only_depositor();
+    set_key(DEPOSITOR, depositor);
+    emit(&DistributorEvent::DepositorChanged { depositor });
+}
+
+////////////////////////////////////////////////////////////////
+//                             SUPPLY CHECKPOINTS
+//////////////////////////////////////////////////////////////*/
+fn _find_timestamp_epoch(escrow: Key, timestamp: u64) -> u64 {
+    let mut min = 0u64;
+    let mut max = escrow::epoch(escrow);
+    for _ in 0..128 {
+        if min >= max {
+            break;
+        }
+        let mid = (min + max + 2) / 2;
+        if escrow::point_history(escrow, mid).ts <= timestamp {
+            min = mid;
+        } else {
+            max = mid - 1;
+        }
+    }
+    min
+}
+
+fn _find_timestamp_user_epoch(
+    escrow: Key,
+    token_id: u64,
+    timestamp: u64,
+    max_user_epoch: u64,
+) -> u64 {
+    let mut min = 0u64;
+    let mut max = max_user_epoch;
+    for _ in 0..128 {
+        if min >= max {
+            break;
+        }
+        let mid = (min + max + 2) / 2;
+        if escrow::user_point_history(escrow, token_id, mid).ts <= timestamp {
+            min = mid;
+        } else {
+            max = mid - 1;
+        }
+    }
+    min
+}
+
+/// @notice Record the escrow's total voting power at every week start up to now
+fn _checkpoint_total_supply() {
+    let escrow = voting_escrow();
+    let mut t: u64 = get_key(TIME_CURSOR).unwrap_or_revert();
+    let rounded_timestamp = current_block_timestamp_seconds() / WEEK * WEEK;
+    escrow::check_point(escrow);
+
+    for _ in 0..20 {
+        if t > rounded_timestamp {
+            break;
+        }
+        let epoch = _find_timestamp_epoch(escrow, t);
+        let point = escrow::point_history(escrow, epoch);
+        set_week_value(VE_SUPPLY, t, balance_at(&point, t.max(point.ts)));
+        t += WEEK;
+    }
+    set_key(TIME_CURSOR, t);
+}
+
+#[no_mangle]
+pub extern "C" fn checkpoint_total_supply() {
+    _checkpoint_total_supply();
+}
+
+/// @notice Voting power of `token_id` at `timestamp` as seen by the distributor
+#[no_mangle]
+pub extern "C" fn ve_for_at() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let timestamp: u64 = runtime::get_named_arg(ARG_TIMESTAMP);
+    let escrow = voting_escrow();
+    let max_user_epoch = escrow::user_point_epoch(escrow, token_id);
+    let epoch = _find_timestamp_user_epoch(escrow, token_id, timestamp, max_user_epoch);
+    let point = escrow::user_point_history(escrow, token_id, epoch);
+    let balance = balance_at(&point, timestamp);
+    runtime::ret(CLValue::from_t(U128::from(balance)).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             CLAIMS
+//////////////////////////////////////////////////////////////*/
+struct ClaimResult {
+    amount: u128,
+    user_epoch: u64,
+    max_user_epoch: u64,
+    week_cursor: u64,
+}
+
+/// @notice Walk the user point history of `token_id` from its cursor up to
+///         `last_token_time` and sum its share of every fully distributed week
+fn _compute_claim(escrow: Key, token_id: u64, last_token_time: u64) -> Option<ClaimResult> {
+    let max_user_epoch = escrow::user_point_epoch(escrow, token_id);
+    if max_user_epoch == 0 {
+        return None;
+    }
+    let start_time: u64 = get_key(START_TIME).unwrap_or_revert();
+
+    let mut week_cursor: u64 = Dict::instance(TIME_CURSOR_OF)
+        .get(&token_id.to_string())
+        .unwrap_or(0);
+    let mut user_epoch = if week_cursor == 0 {
+        _find_timestamp_user_epoch(escrow, token_id, start_time, max_user_epoch)
+    } else {
+        Dict::instance(USER_EPOCH_OF)
+            .get(&token_id.to_string())
+            .unwrap_or(0)
+    };
+    if user_epoch == 0 {
+        user_epoch = 1;
+    }
+
+    let mut user_point = escrow::user_point_history(escrow, token_id, user_epoch);
+    if week_cursor == 0 {
+        week_cursor = (user_point.ts + WEEK - 1) / WEEK * WEEK;
+    }
+    if week_cursor >= last_token_time {
+        return None;
+    }
+    if week_cursor < start_time {
+        week_cursor = start_time;
+    }
+
+    let mut old_user_point = Point::default();
+    let mut amount = 0u128;
+    for _ in 0..50 {
+        if week_cursor >= last_token_time {
+            break;
+        }
+        if week_cursor >= user_point.ts && user_epoch <= max_user_epoch {
+            user_epoch += 1;
+            old_user_point = user_point;
+            user_point = if user_epoch > max_user_epoch {
+                Point::default()
+            } else {
+                escrow::user_point_history(escrow, token_id, user_epoch)
+            };
+        } else {
+            let balance = balance_at(&old_user_point, week_cursor);
+            if balance == 0 && user_epoch > max_user_epoch {
+                break;
+            }
+            let supply = week_value(VE_SUPPLY, week_cursor);
+            if balance > 0 && supply > 0 {
+                amount += mul_div(balance, week_value(TOKENS_PER_WEEK, week_cursor), supply);
+            }
+            week_cursor += WEEK;
+        }
+    }
+
+    Some(ClaimResult {
+        amount,
+        user_epoch: core::cmp::min(max_user_epoch, user_epoch - 1),
+        max_user_epoch,
+        week_cursor,
+    })
+}
+
+fn _last_distributed_week() -> u64 {
+    let last_token_time: u64 = get_key(LAST_TOKEN_TIME).unwrap_or_revert();
+    last_token_time / WEEK * WEEK
+}
+
+/// @notice Claim for `token_id` and pay it out. Rewards go back into the lock when
+///         the owner asks for it and the lock is still running, to the owner otherwise.
+///         Permanent locks, managed NFTs among them, always compound. Only locks of
+///         the reward token itself can take the reward, any other lock is paid out.
+fn _claim(escrow: Key, token_id: u64, last_token_time: u64, compound: bool) -> u128 {
+    // Burned locks have nobody to pay, their rewards stay unclaimed
+    let owner = match escrow::owner_of(escrow, token_id) {
+        Some(owner) => owner,
+        None => return 0,
+    };
+    let result = match _compute_claim(escrow, token_id, last_token_time) {
+        Some(result) => result,
+        None => return 0,
+    };
+    Dict::instance(USER_EPOCH_OF).set(&token_id.to_string(), result.user_epoch);
+    Dict::instance(TIME_CURSOR_OF).set(&token_id.to_string(), result.week_cursor);
+
+    let amount = result.amount;
+    let compounded = escrow::locked_asset(escrow, token_id) == reward_token()
+        && (escrow::locked_is_permanent(escrow, token_id)
+            || (compound
+                && utils::get_immediate_caller_key() == owner
+                && escrow::locked_end(escrow, token_id) > current_block_timestamp_seconds()));
+    if amount != 0 {
+        set_key(TOKEN_LAST_BALANCE, U128::from(token_last_balance() - amount));
+        if compounded {
+            token::approve(reward_token(), token_standard(), escrow, amount);
+            escrow::deposit_for(escrow, token_id, amount);
+        } else {
+            token::transfer(reward_token(), token_standard(), owner, amount);
+        }
+    }
+
+    emit(&DistributorEvent::Claimed {
+        token_id,
+        amount,
+        claim_epoch: result.user_epoch,
+        max_epoch: result.max_user_epoch,
+        compounded,
+    });
+    amount
+}
+
+fn _checkpoint_total_supply_if_due() {
+    let time_cursor: u64 = get_key(TIME_CURSOR).unwrap_or_revert();
+    if current_block_timestamp_seconds() >= time_cursor {
+        _checkpoint_total_supply();
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn claimable() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let amount = _compute_claim(voting_escrow(), token_id, _last_distributed_week())
+        .map(|result| result.amount)
+        .unwrap_or(0);
+    runtime::ret(CLValue::from_t(U128::from(amount)).unwrap_or_revert());
+}
+
+/// @param compound Deposit the reward into the lock instead of paying it out, only
+///                 honoured when called by the owner
+#[no_mangle]
+pub extern "C" fn claim() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let compound: bool = runtime::get_named_arg(ARG_COMPOUND);
+    _checkpoint_total_supply_if_due();
+    let amount = _claim(voting_escrow(), token_id, _last_distributed_week(), compound);
+    runtime::ret(CLValue::from_t(U128::from(amount)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn claim_many() {
+    let token_ids: Vec<U256> = runtime::get_named_arg(ARG_TOKEN_IDS);
+    let compound: bool = runtime::get_named_arg(ARG_COMPOUND);
+    require(
+        token_ids.len() <= MAX_CLAIM_BATCH,
+        DistributorError::TooManyTokenIds,
+    );
+    _checkpoint_total_supply_if_due();
+    let escrow = voting_escrow();
+    let last_token_time = _last_distributed_week();
+    let total = token_ids.iter().fold(0u128, |total, token_id| {
+        total + _claim(escrow, token_id.as_u64(), last_token_time, compound)
+    });
+    runtime::ret(CLValue::from_t(U128::from(total)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn tokens_per_week() {
+    let week: u64 = runtime::get_named_arg(ARG_WEEK);
+    runtime::ret(CLValue::from_t(U128::from(week_value(TOKENS_PER_WEEK, week))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn ve_supply() {
+    let week: u64 = runtime::get_named_arg(ARG_WEEK);
+    runtime::ret(CLValue::from_t(U128::from(week_value(VE_SUPPLY, week))).unwrap_or_revert());
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "checkpoint_token",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "checkpoint_total_supply",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_depositor",
+        vec![Parameter::new(ARG_DEPOSITOR, Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "ve_for_at",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_TIMESTAMP, u64::cl_type()),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claimable",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_COMPOUND, bool::cl_type()),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_many",
+        vec![
+            Parameter::new(ARG_TOKEN_IDS, Vec::<U256>::cl_type()),
+            Parameter::new(ARG_COMPOUND, bool::cl_type()),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "tokens_per_week",
+        vec![Parameter::new(ARG_WEEK, u64::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "ve_supply",
+        vec![Parameter::new(ARG_WEEK, u64::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
@@ -0,0 +1,16 @@
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum DistributorError {
+    NotDepositor = 1,
+    NotOwner = 2,
+    TooManyTokenIds = 3,
+    InvalidStartTime = 4,
+}
+
+impl From<DistributorError> for ApiError {
+    fn from(e: DistributorError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
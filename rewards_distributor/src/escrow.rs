@@ -0,0 +1,172 @@
+//! Calls into the voting escrow, addressed by its package hash so escrow upgrades are followed
+use alloc::vec::Vec;
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    runtime_args, CLType, CLTyped, ContractPackageHash, Key, RuntimeArgs, U128, U256,
+};
+use core::convert::TryInto;
+
+/// Mirror of the escrow's `Point`, i128 fields are 16 little-endian bytes there
+#[derive(Clone, Default)]
+pub struct Point {
+    pub bias: i128,
+    pub slope: i128,
+    pub ts: u64,
+    pub blk: u64,
+}
+
+fn i128_from_bytes(bytes: &[u8]) -> Result<(i128, &[u8]), bytesrepr::Error> {
+    if bytes.len() < 16 {
+        return Err(bytesrepr::Error::EarlyEndOfStream);
+    }
+    let (head, remainder) = bytes.split_at(16);
+    Ok((i128::from_le_bytes(head.try_into().unwrap()), remainder))
+}
+
+impl ToBytes for Point {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend_from_slice(&self.bias.to_le_bytes());
+        result.extend_from_slice(&self.slope.to_le_bytes());
+        result.extend(self.ts.to_bytes()?);
+        result.extend(self.blk.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        16 * 2 + self.ts.serialized_length() + self.blk.serialized_length()
+    }
+}
+
+impl FromBytes for Point {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (bias, remainder) = i128_from_bytes(bytes)?;
+        let (slope, remainder) = i128_from_bytes(remainder)?;
+        let (ts, remainder) = u64::from_bytes(remainder)?;
+        let (blk, remainder) = u64::from_bytes(remainder)?;
+        Ok((
+            Point {
+                bias,
+                slope,
+                ts,
+                blk,
+            },
+            remainder,
+        ))
+    }
+}
+
+impl CLTyped for Point {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+fn package(escrow: Key) -> ContractPackageHash {
+    ContractPackageHash::new(escrow.into_hash().unwrap_or_revert())
+}
+
+pub fn check_point(escrow: Key) {
+    let _: () = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "check_point",
+        runtime_args! {},
+    );
+}
+
+pub fn epoch(escrow: Key) -> u64 {
+    runtime::call_versioned_contract(package(escrow), None, "epoch", runtime_args! {})
+}
+
+pub fn point_history(escrow: Key, epoch: u64) -> Point {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "point_history",
+        runtime_args! {
+            "epoch_index" => epoch
+        },
+    )
+}
+
+pub fn user_point_epoch(escrow: Key, token_id: u64) -> u64 {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "user_point_epoch",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn user_point_history(escrow: Key, token_id: u64, epoch: u64) -> Point {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "user_point_history",
+        runtime_args! {
+            "token_id" => U256::from(token_id),
+            "epoch_index" => epoch
+        },
+    )
+}
+
+pub fn owner_of(escrow: Key, token_id: u64) -> Option<Key> {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "owner_of",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn locked_end(escrow: Key, token_id: u64) -> u64 {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "locked_end",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn locked_is_permanent(escrow: Key, token_id: u64) -> bool {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "locked_is_permanent",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+/// @notice Token locked by `token_id`
+pub fn locked_asset(escrow: Key, token_id: u64) -> Key {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "locked_asset",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn deposit_for(escrow: Key, token_id: u64, amount: u128) {
+    let _: () = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "deposit_for",
+        runtime_args! {
+            "token_id" => U256::from(token_id),
+            "amount" => U128::from(amount)
+        },
+    );
+}
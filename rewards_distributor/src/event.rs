@@ -0,0 +1,53 @@
+use alloc::{collections::BTreeMap, string::ToString};
+use casper_contract::contract_api::storage;
+use casper_types::{Key, URef};
+
+use crate::utils;
+
+pub enum DistributorEvent {
+    CheckpointToken {
+        time: u64,
+        tokens: u128,
+    },
+    Claimed {
+        token_id: u64,
+        amount: u128,
+        claim_epoch: u64,
+        max_epoch: u64,
+        compounded: bool,
+    },
+    DepositorChanged {
+        depositor: Key,
+    },
+}
+
+pub fn emit(event: &DistributorEvent) {
+    let mut param = BTreeMap::new();
+    param.insert("contract_package_hash", utils::get_self_key().to_string());
+    match event {
+        DistributorEvent::CheckpointToken { time, tokens } => {
+            param.insert("event_type", "rd_checkpoint_token".to_string());
+            param.insert("time", time.to_string());
+            param.insert("tokens", tokens.to_string());
+        }
+        DistributorEvent::Claimed {
+            token_id,
+            amount,
+            claim_epoch,
+            max_epoch,
+            compounded,
+        } => {
+            param.insert("event_type", "rd_claimed".to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("amount", amount.to_string());
+            param.insert("claim_epoch", claim_epoch.to_string());
+            param.insert("max_epoch", max_epoch.to_string());
+            param.insert("compounded", compounded.to_string());
+        }
+        DistributorEvent::DepositorChanged { depositor } => {
+            param.insert("event_type", "rd_depositor_changed".to_string());
+            param.insert("depositor", depositor.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
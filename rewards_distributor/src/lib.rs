@@ -0,0 +1,10 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+
+pub mod distributor;
+pub mod error;
+pub mod escrow;
+pub mod event;
+
+pub use common::{dict, token, utils};
@@ -4,15 +4,25 @@ use casper_engine_test_support::{
 };
 
 use casper_types::{
-    account::AccountHash, bytesrepr::FromBytes, CLTyped, runtime_args, system::mint,
-    ContractHash, ContractPackageHash, Key, PublicKey, RuntimeArgs, crypto::SecretKey, U256, U128
+    account::AccountHash, bytesrepr::{Bytes, FromBytes, ToBytes}, CLTyped, runtime_args, system::mint,
+    ContractHash, ContractPackageHash, Key, PublicKey, RuntimeArgs, crypto::SecretKey, U256, U128,
+    ApiError
 };
+use casper_execution_engine::core::{engine_state, execution};
 use std::collections::BTreeMap;
 use std::convert::TryInto;
 
 const EXAMPLE_ERC20_TOKEN: &str = "erc20_token.wasm";
 const TEST_SESSION: &str = "test-session.wasm";
 const VE_CONTRACT: &str = "ve.wasm";
+// Built from the schema version 1 escrow by `make build-ve-schema1`
+const VE_SCHEMA1_CONTRACT: &str = "ve_schema1.wasm";
+const REWARDS_DISTRIBUTOR_CONTRACT: &str = "rewards_distributor.wasm";
+const MINTER_CONTRACT: &str = "minter.wasm";
+const VOTER_CONTRACT: &str = "voter.wasm";
+const GAUGE_CONTRACT: &str = "gauge.wasm";
+const BRIBE_CONTRACT: &str = "bribe.wasm";
+const GOVERNOR_CONTRACT: &str = "governor.wasm";
 const ARG_NAME: &str = "name";
 const ARG_SYMBOL: &str = "symbol";
 const ARG_DECIMALS: &str = "decimals";
@@ -21,6 +31,35 @@ const ARG_NEW_MINTER: &str = "new_minter";
 const RESULT_KEY: &str = "result";
 const TOKEN_TOTAL_SUPPLY: u128 = 1_000_000_000_000_000_000_000_000_000;
 const ERC20_TOKEN_CONTRACT_KEY: &str = "erc20_token_contract";
+const ERR_CONTRACT_LOCKED: u16 = 148;
+const ERR_ALREADY_VOTED_THIS_EPOCH: u16 = 3;
+const ERR_BRIBE_NOT_WHITELISTED: u16 = 6;
+const ERR_INVALID_TOKEN_STANDARD: u16 = 100;
+const ERR_CHECKPOINT_REQUIRED: u16 = 164;
+const ERR_NOT_TEAM: u16 = 149;
+const ERR_MISSING_ROLE: u16 = 166;
+const ERR_LOCKS_PAUSED: u16 = 170;
+const ERR_DEPOSITS_PAUSED: u16 = 171;
+const ERR_MERGES_PAUSED: u16 = 172;
+const ERR_DELEGATION_PAUSED: u16 = 173;
+const ERR_TRANSFERS_PAUSED: u16 = 174;
+const ERR_SMART_WALLET_NOT_ALLOWED: u16 = 175;
+const ERR_OPERATION_NOT_QUEUED: u16 = 178;
+const ERR_OPERATION_NOT_READY: u16 = 179;
+const ERR_OPERATION_EXPIRED: u16 = 180;
+const ERR_PRIVILEGED_ROLE: u16 = 197;
+const GRACE_PERIOD: u64 = 14 * DAY;
+const CURRENT_SCHEMA_VERSION: u32 = 13;
+const WEEK: u64 = 7 * 24 * 3600;
+const DAY: u64 = 24 * 3600;
+const LOCK_AMOUNT: u128 = 1_000_000_000_000_000_000_000;
+const ESCROW_TYPE_NORMAL: u8 = 0;
+const ESCROW_TYPE_LOCKED: u8 = 1;
+const PROPOSAL_PENDING: u8 = 0;
+const PROPOSAL_ACTIVE: u8 = 1;
+const PROPOSAL_SUCCEEDED: u8 = 4;
+const PROPOSAL_QUEUED: u8 = 5;
+const PROPOSAL_EXECUTED: u8 = 7;
 
 fn get_token_key_name(symbol: String) -> String {
     ERC20_TOKEN_CONTRACT_KEY.to_owned() + "_" + &symbol
@@ -97,6 +136,78 @@ fn exec_call(builder: &mut InMemoryWasmTestBuilder, account_hash: AccountHash, c
     }
 }
 
+fn exec_call_at(builder: &mut InMemoryWasmTestBuilder, account_hash: AccountHash, contract_hash: ContractHash, fun_name: &str, args: RuntimeArgs, time: u64, expect_success: bool) {
+    let request = ExecuteRequestBuilder::contract_call_by_hash(
+        account_hash,
+        contract_hash,
+        fun_name,
+        args
+    ).with_block_time(time * 1000).build();
+    if expect_success {
+        builder.exec(request).expect_success().commit();
+    } else {
+        builder.exec(request).expect_failure();
+    }
+}
+
+// Reads a view through one of the test session `call_*` getters at block time `time`
+fn view_at<T: FromBytes + CLTyped>(
+    builder: &mut InMemoryWasmTestBuilder,
+    getter: &str,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+    time: u64
+) -> T {
+    let test_session = get_test_session(builder);
+    let exec_request = ExecuteRequestBuilder::versioned_contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        test_session,
+        None,
+        getter,
+        runtime_args! {
+            "contract_hash" => contract_hash,
+            "entry_point" => entry_point.to_string(),
+            "args" => Bytes::from(args.to_bytes().unwrap())
+        },
+    )
+    .with_block_time(time * 1000)
+    .build();
+    builder.exec(exec_request).expect_success().commit();
+
+    get_test_result(builder, test_session)
+}
+
+// Calls `entry_point` of `contract_hash` from the `test_session` contract instead of an account
+fn exec_from_contract_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    test_session: ContractPackageHash,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+    time: u64,
+    expect_success: bool
+) {
+    let request = ExecuteRequestBuilder::versioned_contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        test_session,
+        None,
+        "call_unit",
+        runtime_args! {
+            "contract_hash" => contract_hash,
+            "entry_point" => entry_point.to_string(),
+            "args" => Bytes::from(args.to_bytes().unwrap())
+        },
+    )
+    .with_block_time(time * 1000)
+    .build();
+    if expect_success {
+        builder.exec(request).expect_success().commit();
+    } else {
+        builder.exec(request).expect_failure();
+    }
+}
+
 fn get_test_session(builder: &mut InMemoryWasmTestBuilder) -> ContractPackageHash {
     let install_test_session = ExecuteRequestBuilder::standard(
         *DEFAULT_ACCOUNT_ADDR,
@@ -178,6 +289,8 @@ fn setup() -> (InMemoryWasmTestBuilder, TestContext) {
             "meta" => BTreeMap::<String, String>::new(),
             "token_contract_hash" => Key::from(usdc_token),
             "art_proxy_contract_hash" => Key::from(usdc_token),
+            "token_standard" => 0u8,
+            "native_lock" => false,
             "contract_name" => "ve".to_string()
         },
     )
@@ -221,6 +334,174 @@ fn setup() -> (InMemoryWasmTestBuilder, TestContext) {
     (builder, tc)
 }
 
+// Installs `wasm` under `contract_name` at block time `time`, returns its contract and package hash
+fn deploy_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    wasm: &str,
+    contract_name: &str,
+    mut args: RuntimeArgs,
+    time: u64
+) -> (ContractHash, ContractPackageHash) {
+    args.insert("contract_name", contract_name.to_string()).unwrap();
+    let request = ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, wasm, args)
+        .with_block_time(time * 1000)
+        .build();
+    builder.exec(request).expect_success().commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let contract_hash = account
+        .named_keys()
+        .get(&format!("{}_contract_hash", contract_name))
+        .and_then(|key| key.into_hash())
+        .map(ContractHash::new)
+        .expect("should have contract hash");
+    let package_hash = account
+        .named_keys()
+        .get(&format!("{}_contract_package_hash", contract_name))
+        .and_then(|key| key.into_hash())
+        .map(ContractPackageHash::new)
+        .expect("should have contract package hash");
+    (contract_hash, package_hash)
+}
+
+fn create_lock_at(builder: &mut InMemoryWasmTestBuilder, tc: &TestContext, lock_duration: u64, time: u64) {
+    exec_call_at(builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_lock", runtime_args! {
+        "amount" => U128::from(LOCK_AMOUNT),
+        "lock_duration" => lock_duration
+    }, time, true);
+}
+
+fn token_balance(builder: &mut InMemoryWasmTestBuilder, tc: &TestContext, address: Key) -> U256 {
+    call_and_get(builder, "get_balance", runtime_args! {
+        "contract_hash" => tc.token,
+        "address" => address
+    })
+}
+
+fn deploy_distributor_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    tc: &TestContext,
+    time: u64
+) -> (ContractHash, ContractPackageHash) {
+    deploy_at(builder, REWARDS_DISTRIBUTOR_CONTRACT, "rewards_distributor", runtime_args! {
+        "voting_escrow" => Key::from(tc.ve_contract_package_hash),
+        "token" => Key::from(tc.token),
+        "token_standard" => 0u8
+    }, time)
+}
+
+fn deploy_voter_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    tc: &TestContext,
+    time: u64
+) -> (ContractHash, ContractPackageHash) {
+    deploy_at(builder, VOTER_CONTRACT, "voter", runtime_args! {
+        "voting_escrow" => Key::from(tc.ve_contract_package_hash),
+        "token" => Key::from(tc.token),
+        "token_standard" => 0u8
+    }, time)
+}
+
+#[derive(Copy, Clone)]
+struct MinterContext {
+    minter: ContractHash,
+    minter_package: ContractPackageHash,
+    distributor_package: ContractPackageHash,
+    voter_package: ContractPackageHash
+}
+
+// Installs a distributor, a voter and a minter emitting `weekly` at first, the minter
+// is made the depositor of the distributor
+fn deploy_minter_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    tc: &TestContext,
+    weekly: u128,
+    time: u64
+) -> MinterContext {
+    let (distributor, distributor_package) = deploy_distributor_at(builder, tc, time);
+    let (_, voter_package) = deploy_voter_at(builder, tc, time);
+    let (minter, minter_package) = deploy_at(builder, MINTER_CONTRACT, "minter", runtime_args! {
+        "token" => Key::from(tc.token),
+        "voting_escrow" => Key::from(tc.ve_contract_package_hash),
+        "rewards_distributor" => Key::from(distributor_package),
+        "voter" => Key::from(voter_package),
+        "weekly" => U128::from(weekly),
+        "token_standard" => 0u8
+    }, time);
+    exec_call_at(builder, *DEFAULT_ACCOUNT_ADDR, distributor, "set_depositor", runtime_args! {
+        "depositor" => Key::from(minter_package)
+    }, time, true);
+    MinterContext {
+        minter,
+        minter_package,
+        distributor_package,
+        voter_package
+    }
+}
+
+// Asserts that the last execution reverted with the user error `expected`
+fn expect_user_error(builder: &InMemoryWasmTestBuilder, expected: u16) {
+    match builder.get_error() {
+        Some(engine_state::Error::Exec(execution::Error::Revert(ApiError::User(code)))) => {
+            assert_eq!(code, expected)
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[derive(Copy, Clone)]
+struct VotingContext {
+    voter: ContractHash,
+    gauge: ContractHash,
+    gauge_package: ContractPackageHash,
+    bribe: ContractHash,
+    bribe_package: ContractPackageHash,
+    pool: Key
+}
+
+// Installs a voter with one pool, its gauge staking and paying the escrow token, and its
+// bribe. The voter holds the voter role of the escrow from `time + 2 * DAY` on.
+fn setup_voting(builder: &mut InMemoryWasmTestBuilder, tc: &TestContext, time: u64) -> VotingContext {
+    let (voter, voter_package) = deploy_voter_at(builder, tc, time);
+    let (gauge, gauge_package) = deploy_at(builder, GAUGE_CONTRACT, "gauge", runtime_args! {
+        "stake" => Key::from(tc.token),
+        "reward_token" => Key::from(tc.token),
+        "voting_escrow" => Key::from(tc.ve_contract_package_hash),
+        "voter" => Key::from(voter_package),
+        "stake_standard" => 0u8,
+        "reward_standard" => 0u8
+    }, time);
+    let (bribe, bribe_package) = deploy_at(builder, BRIBE_CONTRACT, "bribe", runtime_args! {
+        "voter" => Key::from(voter_package),
+        "voting_escrow" => Key::from(tc.ve_contract_package_hash)
+    }, time);
+    let pool = Key::Hash([7u8; 32]);
+    exec_call_at(builder, *DEFAULT_ACCOUNT_ADDR, voter, "register_gauge", runtime_args! {
+        "pool" => pool,
+        "gauge" => Key::from(gauge_package),
+        "bribe" => Key::from(bribe_package)
+    }, time, true);
+
+    exec_call_at(builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "propose_voter", runtime_args! {
+        "new_voter" => Key::from(voter_package)
+    }, time, true);
+    exec_call_at(builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 0u64
+    }, time + 2 * DAY, true);
+    exec_call_at(builder, *DEFAULT_ACCOUNT_ADDR, voter, "accept_voter_role", runtime_args! {}, time + 2 * DAY, true);
+
+    VotingContext {
+        voter,
+        gauge,
+        gauge_package,
+        bribe,
+        bribe_package,
+        pool
+    }
+}
+
 #[test]
 fn test_create_lock() {
     let (mut builder, tc) = setup();
@@ -264,3 +545,1002 @@ fn test_create_lock() {
 }
 
 
+
+#[test]
+fn test_reentrant_token_is_rejected() {
+    let (mut builder, _) = setup();
+    get_test_session(&mut builder);
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let malicious_token = account
+        .named_keys()
+        .get("test_session_contract_hash")
+        .and_then(|key| key.into_hash())
+        .map(ContractHash::new)
+        .expect("should have contract hash");
+
+    let deploy_ve = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        VE_CONTRACT,
+        runtime_args! {
+            ARG_NAME => "EVIL-VE".to_string(),
+            ARG_SYMBOL => "EVIL".to_string(),
+            "meta" => BTreeMap::<String, String>::new(),
+            "token_contract_hash" => Key::from(malicious_token),
+            "art_proxy_contract_hash" => Key::from(malicious_token),
+            "token_standard" => 0u8,
+            "native_lock" => false,
+            "contract_name" => "evil_ve".to_string()
+        },
+    )
+    .build();
+    builder.exec(deploy_ve).expect_success().commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let ve_contract_hash = account
+        .named_keys()
+        .get("evil_ve_contract_hash")
+        .and_then(|key| key.into_hash())
+        .map(ContractHash::new)
+        .expect("should have contract hash");
+
+    // the token calls back into create_lock while the first create_lock is running
+    exec_call(&mut builder, *DEFAULT_ACCOUNT_ADDR, ve_contract_hash, "create_lock", runtime_args! {
+        "amount" => U128::from(1_000_000_000_000_000_000u128),
+        "lock_duration" => 7u64 * 24 * 3600
+    }, false);
+    expect_user_error(&builder, ERR_CONTRACT_LOCKED);
+}
+
+#[test]
+fn test_locks_created_in_one_block_are_all_delegated() {
+    let (mut builder, tc) = setup();
+    let now = 10 * WEEK + 100;
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    for _ in 0..2 {
+        exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_lock", runtime_args! {
+            "amount" => U128::from(1_000_000_000_000_000_000_000u128),
+            "lock_duration" => 4 * WEEK
+        }, now, true);
+    }
+
+    let mut expected = U128::zero();
+    for token_id in 1..=2u64 {
+        let balance: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+            "token_id" => U256::from(token_id)
+        }, now);
+        assert!(balance > U128::zero());
+        expected += balance;
+    }
+
+    // Both mints land on the same checkpoint of the owner instead of replacing each other
+    let votes: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "get_votes", runtime_args! {
+        "address" => owner
+    }, now);
+    assert_eq!(votes, expected);
+}
+
+#[test]
+fn test_merge_into_higher_weight_lock_keeps_supply_in_sync() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    let t1 = t0 + 3 * 24 * 3600;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_lock", runtime_args! {
+        "amount" => U128::from(1_000_000_000_000_000_000_000u128),
+        "lock_duration" => 4 * WEEK
+    }, t0, true);
+
+    // Halve the weight of the underlying so the second lock is weighted lower
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "set_asset_weight", runtime_args! {
+        "asset" => Key::from(tc.token),
+        "weight" => 5_000u64
+    }, t0, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 0u64
+    }, t1, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_lock", runtime_args! {
+        "amount" => U128::from(1_000_000_000_000_000_000_000u128),
+        "lock_duration" => 4 * WEEK
+    }, t1, true);
+
+    let before: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "ve_total_supply", runtime_args! {}, t1);
+    let balance1: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    let balance2: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(2)
+    }, t1);
+    assert_eq!(before, balance1 + balance2);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "merge", runtime_args! {
+        "from" => U256::from(2),
+        "to" => U256::from(1)
+    }, t1, true);
+
+    for t in [t1, t1 + WEEK] {
+        let supply: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "ve_total_supply", runtime_args! {}, t);
+        let merged: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+            "token_id" => U256::from(1)
+        }, t);
+        assert_eq!(supply, merged);
+    }
+    let after: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "ve_total_supply", runtime_args! {}, t1);
+    assert!(after < before);
+}
+
+#[test]
+fn test_distributor_pays_out_or_compounds_a_week_later() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let reward: u128 = 1_000_000_000_000_000_000;
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+
+    // Everything sent in the week starting at 11 * WEEK is credited to that week
+    let t1 = 11 * WEEK + 100;
+    let (distributor, distributor_package) = deploy_distributor_at(&mut builder, &tc, t1);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.token, "transfer", runtime_args! {
+        "recipient" => Key::from(distributor_package),
+        "amount" => U256::from(reward)
+    }, t1, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, distributor, "checkpoint_token", runtime_args! {}, t1, true);
+    let per_week: U128 = view_at(&mut builder, "call_u128", distributor, "tokens_per_week", runtime_args! {
+        "week" => 11 * WEEK
+    }, t1);
+    assert_eq!(per_week, U128::from(reward));
+
+    // The week is claimable once the next token checkpoint is past its end
+    let t2 = 12 * WEEK + 100;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, distributor, "checkpoint_token", runtime_args! {}, t2, true);
+
+    let before = token_balance(&mut builder, &tc, owner);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, distributor, "claim", runtime_args! {
+        "token_id" => U256::from(1),
+        "compound" => false
+    }, t2, true);
+    let paid = token_balance(&mut builder, &tc, owner) - before;
+    assert!(paid > U256::zero() && paid <= U256::from(reward));
+
+    // Both locks hold the reward token, the equal share of the second goes into its lock
+    let supply_before: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "asset_supply", runtime_args! {
+        "asset" => Key::from(tc.token)
+    }, t2);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, distributor, "claim", runtime_args! {
+        "token_id" => U256::from(2),
+        "compound" => true
+    }, t2, true);
+    let supply_after: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "asset_supply", runtime_args! {
+        "asset" => Key::from(tc.token)
+    }, t2);
+    assert_eq!(token_balance(&mut builder, &tc, owner), before + paid);
+    assert_eq!(U256::from((supply_after - supply_before).as_u128()), paid);
+
+    // Nothing is left to claim for the same week
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, distributor, "claim", runtime_args! {
+        "token_id" => U256::from(1),
+        "compound" => false
+    }, t2, true);
+    assert_eq!(token_balance(&mut builder, &tc, owner), before + paid);
+}
+
+#[test]
+fn test_minter_emits_once_per_week_and_splits_the_emission() {
+    let (mut builder, tc) = setup();
+    let weekly: u128 = 10_000_000_000_000_000_000_000_000_000;
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let mc = deploy_minter_at(&mut builder, &tc, weekly, t0);
+    // Prefunded, so the emissions are paid without minting
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.token, "transfer", runtime_args! {
+        "recipient" => Key::from(mc.minter_package),
+        "amount" => U256::from(3 * weekly)
+    }, t0, true);
+
+    // Nothing is emitted before the week boundary following the install
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, mc.minter, "update_period", runtime_args! {}, t0 + DAY, true);
+    assert_eq!(token_balance(&mut builder, &tc, Key::from(mc.voter_package)), U256::zero());
+    assert_eq!(token_balance(&mut builder, &tc, Key::from(mc.distributor_package)), U256::zero());
+
+    let t1 = 11 * WEEK + 100;
+    let emission: U128 = view_at(&mut builder, "call_u128", mc.minter, "weekly_emission", runtime_args! {}, t1);
+    assert_eq!(emission, U128::from(weekly * 990 / 1000));
+    let ve_supply: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "ve_total_supply", runtime_args! {}, t1);
+    let circulating: U128 = view_at(&mut builder, "call_u128", mc.minter, "circulating_supply", runtime_args! {}, t1);
+    let rebase: U128 = view_at(&mut builder, "call_u128", mc.minter, "calculate_growth", runtime_args! {
+        "minted" => emission
+    }, t1);
+    let expected = U256::from(emission.as_u128()) * U256::from(ve_supply.as_u128()) / U256::from(circulating.as_u128());
+    assert!(rebase > U128::zero());
+    assert_eq!(U256::from(rebase.as_u128()), expected);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, mc.minter, "update_period", runtime_args! {}, t1, true);
+    let voter_balance = token_balance(&mut builder, &tc, Key::from(mc.voter_package));
+    let distributor_balance = token_balance(&mut builder, &tc, Key::from(mc.distributor_package));
+    assert_eq!(voter_balance, U256::from(emission.as_u128()));
+    assert_eq!(distributor_balance, U256::from(rebase.as_u128()));
+
+    // A second call in the same week emits nothing
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, mc.minter, "update_period", runtime_args! {}, t1 + DAY, true);
+    assert_eq!(token_balance(&mut builder, &tc, Key::from(mc.voter_package)), voter_balance);
+    assert_eq!(token_balance(&mut builder, &tc, Key::from(mc.distributor_package)), distributor_balance);
+}
+
+#[test]
+fn test_minter_falls_back_to_the_tail_emission() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let mc = deploy_minter_at(&mut builder, &tc, 1, t0);
+
+    let circulating: U128 = view_at(&mut builder, "call_u128", mc.minter, "circulating_supply", runtime_args! {}, t0);
+    assert_eq!(circulating, U128::from(TOKEN_TOTAL_SUPPLY - LOCK_AMOUNT));
+    let emission: U128 = view_at(&mut builder, "call_u128", mc.minter, "weekly_emission", runtime_args! {}, t0);
+    assert_eq!(emission, circulating * U128::from(2) / U128::from(1000));
+}
+
+#[test]
+fn test_vote_once_per_epoch() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let vc = setup_voting(&mut builder, &tc, t0);
+
+    let t1 = t0 + 2 * DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.voter, "vote", runtime_args! {
+        "token_id" => U256::from(1),
+        "pools" => vec![vc.pool],
+        "weights" => vec![U128::one()]
+    }, t1, true);
+    let power: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    let weight: U128 = view_at(&mut builder, "call_u128", vc.voter, "weights", runtime_args! {
+        "pool" => vc.pool
+    }, t1);
+    let used: U128 = view_at(&mut builder, "call_u128", vc.voter, "used_weights", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    assert!(weight > U128::zero());
+    assert_eq!(weight, power);
+    assert_eq!(used, weight);
+
+    // A second vote in the same epoch is rejected
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.voter, "vote", runtime_args! {
+        "token_id" => U256::from(1),
+        "pools" => vec![vc.pool],
+        "weights" => vec![U128::one()]
+    }, t1 + DAY, false);
+    expect_user_error(&builder, ERR_ALREADY_VOTED_THIS_EPOCH);
+
+    // The next epoch replaces the old vote with the decayed power
+    let t2 = 11 * WEEK + 100;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.voter, "vote", runtime_args! {
+        "token_id" => U256::from(1),
+        "pools" => vec![vc.pool],
+        "weights" => vec![U128::one()]
+    }, t2, true);
+    let power: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t2);
+    let weight: U128 = view_at(&mut builder, "call_u128", vc.voter, "weights", runtime_args! {
+        "pool" => vc.pool
+    }, t2);
+    assert_eq!(weight, power);
+}
+
+#[test]
+fn test_gauge_boosts_a_stake_with_an_attached_lock() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let stake: u128 = 1_000_000_000_000_000_000_000;
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let vc = setup_voting(&mut builder, &tc, t0);
+
+    let t1 = t0 + 2 * DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.token, "approve", runtime_args! {
+        "spender" => Key::from(vc.gauge_package),
+        "amount" => U256::from(2 * stake)
+    }, t1, true);
+
+    // Without a lock only the base share of the stake counts
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.gauge, "deposit", runtime_args! {
+        "amount" => U128::from(stake),
+        "token_id" => U256::zero()
+    }, t1, true);
+    let derived: U128 = view_at(&mut builder, "call_u128", vc.gauge, "derived_balance", runtime_args! {
+        "account" => owner
+    }, t1);
+    assert_eq!(derived, U128::from(stake * 40 / 100));
+
+    // Attaching the only lock adds the boost share scaled by its part of the voting power
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.gauge, "deposit", runtime_args! {
+        "amount" => U128::from(stake),
+        "token_id" => U256::from(1)
+    }, t1, true);
+    let attached: U256 = view_at(&mut builder, "call_u256", vc.gauge, "token_ids", runtime_args! {
+        "account" => owner
+    }, t1);
+    assert_eq!(attached, U256::from(1));
+
+    let power: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    let ve_total: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "ve_total_supply", runtime_args! {}, t1);
+    let boost = U256::from(2 * stake) * U256::from(power.as_u128()) / U256::from(ve_total.as_u128())
+        * U256::from(60u64)
+        / U256::from(100u64);
+    let expected = std::cmp::min(2 * stake * 40 / 100 + boost.as_u128(), 2 * stake);
+    let derived: U128 = view_at(&mut builder, "call_u128", vc.gauge, "derived_balance", runtime_args! {
+        "account" => owner
+    }, t1);
+    assert!(expected > 2 * stake * 40 / 100);
+    assert_eq!(derived, U128::from(expected));
+}
+
+#[test]
+fn test_bribe_pays_the_voters_of_a_finished_epoch() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let reward: u128 = 1_000_000_000_000_000_000;
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let vc = setup_voting(&mut builder, &tc, t0);
+
+    let t1 = t0 + 2 * DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.voter, "vote", runtime_args! {
+        "token_id" => U256::from(1),
+        "pools" => vec![vc.pool],
+        "weights" => vec![U128::one()]
+    }, t1, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.token, "approve", runtime_args! {
+        "spender" => Key::from(vc.bribe_package),
+        "amount" => U256::from(reward)
+    }, t1, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.bribe, "notify_reward_amount", runtime_args! {
+        "token" => Key::from(tc.token),
+        "amount" => U128::from(reward)
+    }, t1, true);
+
+    // Only tokens whitelisted by the voter can be added as rewards
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.bribe, "notify_reward_amount", runtime_args! {
+        "token" => Key::Hash([9u8; 32]),
+        "amount" => U128::one()
+    }, t1, false);
+    expect_user_error(&builder, ERR_BRIBE_NOT_WHITELISTED);
+
+    // The only voter of the epoch earns all of it once the epoch is over
+    let t2 = 11 * WEEK + 100;
+    let earned: U128 = view_at(&mut builder, "call_u128", vc.bribe, "earned", runtime_args! {
+        "token" => Key::from(tc.token),
+        "token_id" => U256::from(1)
+    }, t2);
+    assert_eq!(earned, U128::from(reward));
+
+    let before = token_balance(&mut builder, &tc, owner);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, vc.bribe, "get_reward", runtime_args! {
+        "token_id" => U256::from(1),
+        "tokens" => vec![Key::from(tc.token)]
+    }, t2, true);
+    assert_eq!(token_balance(&mut builder, &tc, owner), before + U256::from(reward));
+}
+
+#[test]
+fn test_voter_keeps_the_standard_of_whitelisted_tokens() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    let (voter, _) = deploy_voter_at(&mut builder, &tc, t0);
+
+    // The emitted token is whitelisted with the standard it was installed with
+    let standard: u8 = view_at(&mut builder, "call_u8", voter, "token_standard", runtime_args! {
+        "token" => Key::from(tc.token)
+    }, t0);
+    assert_eq!(standard, 0);
+
+    let cep18 = Key::Hash([9u8; 32]);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, voter, "whitelist_token", runtime_args! {
+        "token" => cep18,
+        "allowed" => true,
+        "token_standard" => 1u8
+    }, t0, true);
+    let standard: u8 = view_at(&mut builder, "call_u8", voter, "token_standard", runtime_args! {
+        "token" => cep18
+    }, t0);
+    assert_eq!(standard, 1);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, voter, "whitelist_token", runtime_args! {
+        "token" => Key::Hash([10u8; 32]),
+        "allowed" => true,
+        "token_standard" => 2u8
+    }, t0, false);
+    expect_user_error(&builder, ERR_INVALID_TOKEN_STANDARD);
+}
+
+#[test]
+fn test_governor_executes_a_passed_proposal() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let (governor, governor_package) = deploy_at(&mut builder, GOVERNOR_CONTRACT, "governor", runtime_args! {
+        "voting_escrow" => Key::from(tc.ve_contract_package_hash),
+        "voting_delay" => DAY,
+        "voting_period" => 3 * DAY,
+        "proposal_threshold" => U128::one(),
+        "quorum_bps" => 400u64,
+        "timelock_delay" => DAY
+    }, t0);
+
+    // The governor proposes a change of its own settings
+    let proposed_at = t0 + 100;
+    let call_args = runtime_args! {
+        "proposal_threshold" => U128::from(5)
+    };
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, governor, "propose", runtime_args! {
+        "target" => Key::from(governor_package),
+        "entry_point" => "set_proposal_threshold".to_string(),
+        "args" => Bytes::from(call_args.to_bytes().unwrap()),
+        "description" => "raise the proposal threshold".to_string()
+    }, proposed_at, true);
+    let snapshot = proposed_at + DAY;
+    let vote_end = snapshot + 3 * DAY;
+    let state: u8 = view_at(&mut builder, "call_u8", governor, "state", runtime_args! {
+        "proposal_id" => 1u64
+    }, snapshot);
+    assert_eq!(state, PROPOSAL_PENDING);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, governor, "cast_vote", runtime_args! {
+        "proposal_id" => 1u64,
+        "support" => 1u8
+    }, snapshot + 1, true);
+    let state: u8 = view_at(&mut builder, "call_u8", governor, "state", runtime_args! {
+        "proposal_id" => 1u64
+    }, snapshot + 1);
+    assert_eq!(state, PROPOSAL_ACTIVE);
+
+    let state: u8 = view_at(&mut builder, "call_u8", governor, "state", runtime_args! {
+        "proposal_id" => 1u64
+    }, vote_end + 1);
+    assert_eq!(state, PROPOSAL_SUCCEEDED);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, governor, "queue", runtime_args! {
+        "proposal_id" => 1u64
+    }, vote_end + 1, true);
+    let state: u8 = view_at(&mut builder, "call_u8", governor, "state", runtime_args! {
+        "proposal_id" => 1u64
+    }, vote_end + 1);
+    assert_eq!(state, PROPOSAL_QUEUED);
+
+    let eta = vote_end + 1 + DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, governor, "execute", runtime_args! {
+        "proposal_id" => 1u64
+    }, eta, true);
+    let state: u8 = view_at(&mut builder, "call_u8", governor, "state", runtime_args! {
+        "proposal_id" => 1u64
+    }, eta);
+    assert_eq!(state, PROPOSAL_EXECUTED);
+    let threshold: U128 = view_at(&mut builder, "call_u128", governor, "get_proposal_threshold", runtime_args! {}, eta);
+    assert_eq!(threshold, U128::from(5));
+}
+
+#[test]
+fn test_deposit_and_withdraw_managed_round_trip() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_managed_lock_for", runtime_args! {
+        "to" => owner
+    }, t0, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "deposit_managed", runtime_args! {
+        "token_id" => U256::from(1),
+        "managed_id" => U256::from(2)
+    }, t0, true);
+
+    // The deposited lock is emptied into shares of the managed lock
+    let escrow_type: u8 = view_at(&mut builder, "call_u8", tc.ve_contract_hash, "escrow_type", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t0);
+    assert_eq!(escrow_type, ESCROW_TYPE_LOCKED);
+    let power: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t0);
+    assert_eq!(power, U128::zero());
+    let shares: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "managed_shares", runtime_args! {
+        "managed_id" => U256::from(2)
+    }, t0);
+    assert!(shares > U128::zero());
+
+    // Withdrawing relocks the full amount for the maximum time
+    let t1 = t0 + WEEK;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "withdraw_managed", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1, true);
+    let escrow_type: u8 = view_at(&mut builder, "call_u8", tc.ve_contract_hash, "escrow_type", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    assert_eq!(escrow_type, ESCROW_TYPE_NORMAL);
+    let shares: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "managed_shares", runtime_args! {
+        "managed_id" => U256::from(2)
+    }, t1);
+    assert_eq!(shares, U128::zero());
+
+    create_lock_at(&mut builder, &tc, 26 * WEEK, t1);
+    let withdrawn: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    let fresh: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(3)
+    }, t1);
+    assert!(withdrawn > U128::zero());
+    assert_eq!(withdrawn, fresh);
+}
+
+#[test]
+fn test_token_delegation_moves_to_the_new_owner_on_transfer() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let account1 = Key::from(get_account1_addr());
+    let account2 = Key::from(get_account2_addr());
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+
+    // Only the delegated token counts for account1, the other one stays with its owner
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "delegate_token", runtime_args! {
+        "token_id" => U256::from(1),
+        "delegatee" => account1
+    }, t0, true);
+    let delegatee: Key = view_at(&mut builder, "call_key", tc.ve_contract_hash, "token_delegate", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t0);
+    assert_eq!(delegatee, account1);
+    let mut balances = Vec::new();
+    for token_id in 1..=2u64 {
+        let balance: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+            "token_id" => U256::from(token_id)
+        }, t0);
+        balances.push(balance);
+    }
+    let votes1: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "get_votes", runtime_args! {
+        "address" => account1
+    }, t0);
+    let owner_votes: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "get_votes", runtime_args! {
+        "address" => owner
+    }, t0);
+    assert_eq!(votes1, balances[0]);
+    assert_eq!(owner_votes, balances[1]);
+
+    // The delegation was chosen by the old owner, the votes follow the token to account2
+    let t1 = t0 + DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "transfer", runtime_args! {
+        "recipient" => account2,
+        "token_ids" => vec![U256::from(1)]
+    }, t1, true);
+    let delegatee: Key = view_at(&mut builder, "call_key", tc.ve_contract_hash, "token_delegate", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    assert_eq!(delegatee, account2);
+    let balance: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1);
+    let votes1: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "get_votes", runtime_args! {
+        "address" => account1
+    }, t1);
+    let votes2: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "get_votes", runtime_args! {
+        "address" => account2
+    }, t1);
+    assert_eq!(votes1, U128::zero());
+    assert_eq!(votes2, balance);
+}
+
+#[test]
+fn test_past_total_supply_decays_across_week_boundaries() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+
+    // With a single lock the supply walked week by week from t0 equals the lock's own power
+    for t in [t0 + DAY, t0 + WEEK, t0 + 2 * WEEK + 3 * DAY] {
+        let supply: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "get_past_total_supply", runtime_args! {
+            "timestamp" => t
+        }, t);
+        let power: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+            "token_id" => U256::from(1)
+        }, t);
+        assert!(power > U128::zero());
+        assert_eq!(supply, power);
+    }
+}
+
+#[test]
+fn test_create_lock_after_a_withdraw_gets_a_fresh_id() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, WEEK, t0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+
+    // Burning token 1 lowers the NFT supply to 1 while token 2 is still live
+    let t1 = 11 * WEEK + 100;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "withdraw", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1, true);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t1);
+
+    let owner_of: Key = call_and_get(&mut builder, "owner_of", runtime_args! {
+        "contract_hash" => tc.ve_contract_hash,
+        "token_id" => U256::from(3)
+    });
+    assert_eq!(owner_of, owner);
+    for token_id in 2..=3u64 {
+        let power: U128 = view_at(&mut builder, "call_u128", tc.ve_contract_hash, "balance_of_nft", runtime_args! {
+            "token_id" => U256::from(token_id)
+        }, t1);
+        assert!(power > U128::zero());
+    }
+}
+
+#[test]
+fn test_upgrade_migrates_a_schema_1_escrow() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let t0 = 10 * WEEK + 100;
+    // Schema 1 predates the token standard and native lock install arguments
+    let (old, _) = deploy_at(&mut builder, VE_SCHEMA1_CONTRACT, "ve_v1", runtime_args! {
+        ARG_NAME => "USDC-VE".to_string(),
+        ARG_SYMBOL => "USDC".to_string(),
+        "meta" => BTreeMap::<String, String>::new(),
+        "token_contract_hash" => Key::from(tc.token),
+        "art_proxy_contract_hash" => Key::from(tc.token)
+    }, t0);
+    let version: u32 = view_at(&mut builder, "call_u32", old, "schema_version", runtime_args! {}, t0);
+    assert_eq!(version, 1);
+
+    // Running the installer again under the same name upgrades the package and migrates it
+    let t1 = t0 + DAY;
+    let (ve, _) = deploy_at(&mut builder, VE_CONTRACT, "ve_v1", runtime_args! {}, t1);
+    assert_ne!(ve, old);
+    let version: u32 = view_at(&mut builder, "call_u32", ve, "schema_version", runtime_args! {}, t1);
+    assert_eq!(version, CURRENT_SCHEMA_VERSION);
+
+    let named_keys = builder
+        .get_contract(ve)
+        .expect("should have the upgraded contract")
+        .named_keys()
+        .clone();
+    for name in ["roles", "paused_flags", "total_locked", "checkpoint_max_weeks"] {
+        assert!(named_keys.contains_key(name), "missing {}", name);
+    }
+    // The team of schema 1 holds every role after the migration
+    for role in ["team", "voter", "migrator", "pauser", "metadata_admin"] {
+        let has_role: bool = view_at(&mut builder, "call_bool", ve, "has_role", runtime_args! {
+            "role" => role.to_string(),
+            "account" => owner
+        }, t1);
+        assert!(has_role, "missing role {}", role);
+    }
+    let paused: u8 = view_at(&mut builder, "call_u8", ve, "paused_flags", runtime_args! {}, t1);
+    assert_eq!(paused, 0);
+    let total_locked: U128 = builder.get_value(ve, "total_locked");
+    assert_eq!(total_locked, U128::zero());
+    let max_weeks: u64 = builder.get_value(ve, "checkpoint_max_weeks");
+    assert_eq!(max_weeks, 52);
+
+    // Nothing is left to migrate, a second run changes nothing
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, ve, "migrate", runtime_args! {}, t1, true);
+    let version: u32 = view_at(&mut builder, "call_u32", ve, "schema_version", runtime_args! {}, t1);
+    assert_eq!(version, CURRENT_SCHEMA_VERSION);
+    let max_weeks: u64 = builder.get_value(ve, "checkpoint_max_weeks");
+    assert_eq!(max_weeks, 52);
+}
+
+#[test]
+fn test_withdraw_after_a_long_idle_period_needs_a_partial_checkpoint() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+
+    // Nothing checkpoints for longer than the default budget of 52 weeks
+    let t1 = t0 + 60 * WEEK;
+    let pending: u64 = view_at(&mut builder, "call_u64", tc.ve_contract_hash, "check_point_pending_weeks", runtime_args! {}, t1);
+    assert!(pending > 52);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "withdraw", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1, false);
+    expect_user_error(&builder, ERR_CHECKPOINT_REQUIRED);
+
+    // A keeper catches the history up, after which the withdraw fits in the budget
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "check_point_partial", runtime_args! {
+        "max_weeks" => 52u64
+    }, t1, true);
+    let pending: u64 = view_at(&mut builder, "call_u64", tc.ve_contract_hash, "check_point_pending_weeks", runtime_args! {}, t1);
+    assert!(pending <= 52);
+
+    let before = token_balance(&mut builder, &tc, owner);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "withdraw", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1, true);
+    assert_eq!(token_balance(&mut builder, &tc, owner), before + U256::from(LOCK_AMOUNT));
+}
+
+#[test]
+fn test_timelock_runs_an_operation_between_its_eta_and_the_grace_period() {
+    let (mut builder, tc) = setup();
+    let t0 = 10 * WEEK + 100;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "set_checkpoint_max_weeks", runtime_args! {
+        "max_weeks" => 10u64
+    }, t0, true);
+
+    // Operation 0 waits out the default delay of two days
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 0u64
+    }, t0 + DAY, false);
+    expect_user_error(&builder, ERR_OPERATION_NOT_READY);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 0u64
+    }, t0 + 2 * DAY, true);
+    let max_weeks: u64 = builder.get_value(tc.ve_contract_hash, "checkpoint_max_weeks");
+    assert_eq!(max_weeks, 10);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 0u64
+    }, t0 + 2 * DAY, false);
+    expect_user_error(&builder, ERR_OPERATION_NOT_QUEUED);
+
+    // Operation 1 is left past its grace period, it can then only be cancelled
+    let t1 = t0 + 2 * DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "set_checkpoint_max_weeks", runtime_args! {
+        "max_weeks" => 20u64
+    }, t1, true);
+    let expired = t1 + 2 * DAY + GRACE_PERIOD + 1;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 1u64
+    }, expired, false);
+    expect_user_error(&builder, ERR_OPERATION_EXPIRED);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "cancel_queued", runtime_args! {
+        "op_id" => 1u64
+    }, expired, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 1u64
+    }, expired, false);
+    expect_user_error(&builder, ERR_OPERATION_NOT_QUEUED);
+    let max_weeks: u64 = builder.get_value(tc.ve_contract_hash, "checkpoint_max_weeks");
+    assert_eq!(max_weeks, 10);
+}
+
+#[test]
+fn test_privileged_roles_only_change_through_the_timelock() {
+    let (mut builder, tc) = setup();
+    let account = Key::from(get_account1_addr());
+    let t0 = 10 * WEEK + 100;
+    let has_migrator = |builder: &mut InMemoryWasmTestBuilder, time: u64| -> bool {
+        view_at(builder, "call_bool", tc.ve_contract_hash, "has_role", runtime_args! {
+            "role" => "migrator".to_string(),
+            "account" => account
+        }, time)
+    };
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "grant_role", runtime_args! {
+        "role" => "migrator".to_string(),
+        "account" => account
+    }, t0, false);
+    expect_user_error(&builder, ERR_PRIVILEGED_ROLE);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "queue_grant_role", runtime_args! {
+        "role" => "migrator".to_string(),
+        "account" => account
+    }, t0, true);
+    assert!(!has_migrator(&mut builder, t0 + DAY));
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 0u64
+    }, t0 + 2 * DAY, true);
+    assert!(has_migrator(&mut builder, t0 + 2 * DAY));
+
+    let t1 = t0 + 2 * DAY;
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "revoke_role", runtime_args! {
+        "role" => "migrator".to_string(),
+        "account" => account
+    }, t1, false);
+    expect_user_error(&builder, ERR_PRIVILEGED_ROLE);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "queue_revoke_role", runtime_args! {
+        "role" => "migrator".to_string(),
+        "account" => account
+    }, t1, true);
+    assert!(has_migrator(&mut builder, t1 + DAY));
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "execute_queued", runtime_args! {
+        "op_id" => 1u64
+    }, t1 + 2 * DAY, true);
+    assert!(!has_migrator(&mut builder, t1 + 2 * DAY));
+}
+
+#[test]
+fn test_pause_flags_stop_their_class_but_not_withdrawals() {
+    let (mut builder, tc) = setup();
+    let owner = Key::from(*DEFAULT_ACCOUNT_ADDR);
+    let account1 = Key::from(get_account1_addr());
+    let pause_all: u8 = 0b1_1111;
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, WEEK, t0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_managed_lock_for", runtime_args! {
+        "to" => owner
+    }, t0, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "deposit_managed", runtime_args! {
+        "token_id" => U256::from(4),
+        "managed_id" => U256::from(5)
+    }, t0, true);
+
+    // Only the team or a pauser may pause
+    exec_call_at(&mut builder, get_account1_addr(), tc.ve_contract_hash, "pause", runtime_args! {
+        "flags" => pause_all
+    }, t0, false);
+    expect_user_error(&builder, ERR_MISSING_ROLE);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "pause", runtime_args! {
+        "flags" => pause_all
+    }, t0, true);
+    let paused: u8 = view_at(&mut builder, "call_u8", tc.ve_contract_hash, "paused_flags", runtime_args! {}, t0);
+    assert_eq!(paused, pause_all);
+
+    let paused_calls = vec![
+        ("create_lock", runtime_args! {
+            "amount" => U128::from(LOCK_AMOUNT),
+            "lock_duration" => 8 * WEEK
+        }, ERR_LOCKS_PAUSED),
+        ("increase_amount", runtime_args! {
+            "token_id" => U256::from(2),
+            "amount" => U128::from(LOCK_AMOUNT)
+        }, ERR_DEPOSITS_PAUSED),
+        ("merge", runtime_args! {
+            "from" => U256::from(2),
+            "to" => U256::from(3)
+        }, ERR_MERGES_PAUSED),
+        ("delegate", runtime_args! {
+            "delegatee" => account1
+        }, ERR_DELEGATION_PAUSED),
+        ("transfer", runtime_args! {
+            "recipient" => account1,
+            "token_ids" => vec![U256::from(2)]
+        }, ERR_TRANSFERS_PAUSED),
+    ];
+    for (entry_point, args, error) in paused_calls {
+        exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, entry_point, args, t0, false);
+        expect_user_error(&builder, error);
+    }
+
+    // Owners can still leave while everything is paused
+    let t1 = t0 + WEEK;
+    let before = token_balance(&mut builder, &tc, owner);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "withdraw", runtime_args! {
+        "token_id" => U256::from(1)
+    }, t1, true);
+    assert_eq!(token_balance(&mut builder, &tc, owner), before + U256::from(LOCK_AMOUNT));
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "withdraw_managed", runtime_args! {
+        "token_id" => U256::from(4)
+    }, t1, true);
+    let escrow_type: u8 = view_at(&mut builder, "call_u8", tc.ve_contract_hash, "escrow_type", runtime_args! {
+        "token_id" => U256::from(4)
+    }, t1);
+    assert_eq!(escrow_type, ESCROW_TYPE_NORMAL);
+
+    // A granted pauser lifts the pause
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "grant_role", runtime_args! {
+        "role" => "pauser".to_string(),
+        "account" => account1
+    }, t1, true);
+    exec_call_at(&mut builder, get_account1_addr(), tc.ve_contract_hash, "unpause", runtime_args! {
+        "flags" => pause_all
+    }, t1, true);
+    let paused: u8 = view_at(&mut builder, "call_u8", tc.ve_contract_hash, "paused_flags", runtime_args! {}, t1);
+    assert_eq!(paused, 0);
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t1);
+}
+
+#[test]
+fn test_smart_wallet_check_covers_deposits_from_a_contract() {
+    let (mut builder, tc) = setup();
+    let amount: u128 = 1_000_000_000_000_000_000;
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let wallet = get_test_session(&mut builder);
+    let wallet_key = Key::from(wallet);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "create_lock_for", runtime_args! {
+        "amount" => U128::from(LOCK_AMOUNT),
+        "lock_duration" => 8 * WEEK,
+        "to" => wallet_key
+    }, t0, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.token, "transfer", runtime_args! {
+        "recipient" => wallet_key,
+        "amount" => U256::from(2 * amount)
+    }, t0, true);
+    exec_from_contract_at(&mut builder, wallet, tc.token, "approve", runtime_args! {
+        "spender" => Key::from(tc.ve_contract_package_hash),
+        "amount" => U256::from(2 * amount)
+    }, t0, true);
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "set_smart_wallet_check", runtime_args! {
+        "enabled" => true
+    }, t0, true);
+
+    // Topping up a lock is refused to a contract that isn't allowed, even its own lock
+    exec_from_contract_at(&mut builder, wallet, tc.ve_contract_hash, "deposit_for", runtime_args! {
+        "token_id" => U256::from(1),
+        "amount" => U128::from(amount)
+    }, t0, false);
+    expect_user_error(&builder, ERR_SMART_WALLET_NOT_ALLOWED);
+    exec_from_contract_at(&mut builder, wallet, tc.ve_contract_hash, "increase_amount", runtime_args! {
+        "token_id" => U256::from(2),
+        "amount" => U128::from(amount)
+    }, t0, false);
+    expect_user_error(&builder, ERR_SMART_WALLET_NOT_ALLOWED);
+
+    // Accounts are never checked
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "deposit_for", runtime_args! {
+        "token_id" => U256::from(1),
+        "amount" => U128::from(amount)
+    }, t0, true);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "allow_smart_wallet", runtime_args! {
+        "address" => wallet_key
+    }, t0, true);
+    exec_from_contract_at(&mut builder, wallet, tc.ve_contract_hash, "deposit_for", runtime_args! {
+        "token_id" => U256::from(1),
+        "amount" => U128::from(amount)
+    }, t0, true);
+    exec_from_contract_at(&mut builder, wallet, tc.ve_contract_hash, "increase_amount", runtime_args! {
+        "token_id" => U256::from(2),
+        "amount" => U128::from(amount)
+    }, t0, true);
+    assert_eq!(token_balance(&mut builder, &tc, wallet_key), U256::zero());
+}
+
+#[test]
+fn test_metadata_admin_role_gates_update_token_meta() {
+    let (mut builder, tc) = setup();
+    let account1 = Key::from(get_account1_addr());
+    let t0 = 10 * WEEK + 100;
+    create_lock_at(&mut builder, &tc, 8 * WEEK, t0);
+    let update_meta = |builder: &mut InMemoryWasmTestBuilder, expect_success: bool| {
+        let mut meta = BTreeMap::new();
+        meta.insert("name".to_string(), "lock 1".to_string());
+        exec_call_at(builder, get_account1_addr(), tc.ve_contract_hash, "update_token_meta", runtime_args! {
+            "token_id" => U256::from(1),
+            "token_meta" => meta
+        }, t0, expect_success);
+    };
+    let is_metadata_admin = |builder: &mut InMemoryWasmTestBuilder| -> bool {
+        view_at(builder, "call_bool", tc.ve_contract_hash, "has_role", runtime_args! {
+            "role" => "metadata_admin".to_string(),
+            "account" => account1
+        }, t0)
+    };
+
+    update_meta(&mut builder, false);
+    expect_user_error(&builder, ERR_MISSING_ROLE);
+
+    // Only the team hands out roles
+    exec_call_at(&mut builder, get_account1_addr(), tc.ve_contract_hash, "grant_role", runtime_args! {
+        "role" => "metadata_admin".to_string(),
+        "account" => account1
+    }, t0, false);
+    expect_user_error(&builder, ERR_NOT_TEAM);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "grant_role", runtime_args! {
+        "role" => "metadata_admin".to_string(),
+        "account" => account1
+    }, t0, true);
+    assert!(is_metadata_admin(&mut builder));
+    update_meta(&mut builder, true);
+
+    exec_call_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, tc.ve_contract_hash, "revoke_role", runtime_args! {
+        "role" => "metadata_admin".to_string(),
+        "account" => account1
+    }, t0, true);
+    assert!(!is_metadata_admin(&mut builder));
+    update_meta(&mut builder, false);
+    expect_user_error(&builder, ERR_MISSING_ROLE);
+}
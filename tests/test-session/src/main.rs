@@ -13,15 +13,18 @@ use alloc::{
 use casper_contract::{
     self,
     contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
 };
 
 use casper_types::{
-    bytesrepr::ToBytes, runtime_args, CLTyped, ContractHash, EntryPoint, EntryPointAccess,
-    EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs, U256, U128, CLType
+    bytesrepr::{Bytes, FromBytes, ToBytes}, runtime_args, system::CallStackElement, CLTyped, ContractHash,
+    EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs,
+    U256, U128, CLType, CLValue
 };
 
 const RESULT_KEY: &str = "result";
 const TEST_SESSION: &str = "test_session";
+const TEST_SESSION_CONTRACT_HASH: &str = "test_session_contract_hash";
 
 fn store_result<T: CLTyped + ToBytes>(result: T) {
     match runtime::get_key(RESULT_KEY) {
@@ -34,6 +37,57 @@ fn store_result<T: CLTyped + ToBytes>(result: T) {
     }
 }
 
+// Calls a view with serialized runtime args and stores what it returned
+fn call_view<T: CLTyped + FromBytes + ToBytes>() {
+    let contract: ContractHash = runtime::get_named_arg("contract_hash");
+    let entry_point: String = runtime::get_named_arg("entry_point");
+    let args: Bytes = runtime::get_named_arg("args");
+    let (args, _) = RuntimeArgs::from_bytes(&args).unwrap_or_revert();
+    let b: T = runtime::call_contract(contract, &entry_point, args);
+    store_result(b);
+}
+
+#[no_mangle]
+extern "C" fn call_u128() {
+    call_view::<U128>();
+}
+
+#[no_mangle]
+extern "C" fn call_u256() {
+    call_view::<U256>();
+}
+
+#[no_mangle]
+extern "C" fn call_u64() {
+    call_view::<u64>();
+}
+
+#[no_mangle]
+extern "C" fn call_u8() {
+    call_view::<u8>();
+}
+
+#[no_mangle]
+extern "C" fn call_u32() {
+    call_view::<u32>();
+}
+
+#[no_mangle]
+extern "C" fn call_bool() {
+    call_view::<bool>();
+}
+
+#[no_mangle]
+extern "C" fn call_key() {
+    call_view::<Key>();
+}
+
+// Calls an entry point returning nothing, the callee sees this contract as its caller
+#[no_mangle]
+extern "C" fn call_unit() {
+    call_view::<()>();
+}
+
 #[no_mangle]
 extern "C" fn get_balance() {
     let token_contract: ContractHash = runtime::get_named_arg("contract_hash");
@@ -199,6 +253,29 @@ extern "C" fn get_admin_balance() {
     store_result(b);
 }
 
+#[no_mangle]
+extern "C" fn balance_of() {
+    runtime::ret(CLValue::from_t(U256::zero()).unwrap_or_revert());
+}
+
+// Malicious token: instead of moving funds it re-enters the escrow that called it
+#[no_mangle]
+extern "C" fn transfer_from() {
+    let escrow = match runtime::get_call_stack().into_iter().rev().nth(1) {
+        Some(CallStackElement::StoredContract { contract_hash, .. }) => contract_hash,
+        _ => runtime::revert(casper_types::ApiError::InvalidCallerInfoRequest),
+    };
+
+    let _: () = runtime::call_contract(
+        escrow,
+        "create_lock",
+        runtime_args! {
+            "amount" => U128::from(1u128),
+            "lock_duration" => 7u64 * 24 * 3600
+        },
+    );
+}
+
 #[no_mangle]
 pub extern "C" fn call() {
     let mut entry_points = EntryPoints::new();
@@ -323,12 +400,50 @@ pub extern "C" fn call() {
     entry_points.add_entry_point(get_allowance_entrypoint);
     entry_points.add_entry_point(get_balance_entrypoint);
     entry_points.add_entry_point(calculate_token_amount_entrypoint);
+    let transfer_from_entrypoint = EntryPoint::new(
+        String::from("transfer_from"),
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type())
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let balance_of_entrypoint = EntryPoint::new(
+        String::from("balance_of"),
+        vec![
+            Parameter::new("address", Key::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
     entry_points.add_entry_point(get_owner_of);
+    for name in ["call_u128", "call_u256", "call_u64", "call_u8", "call_u32", "call_bool", "call_key", "call_unit"] {
+        entry_points.add_entry_point(EntryPoint::new(
+            String::from(name),
+            vec![
+                Parameter::new("contract_hash", ContractHash::cl_type()),
+                Parameter::new("entry_point", String::cl_type()),
+                Parameter::new("args", Bytes::cl_type())
+            ],
+            <()>::cl_type(),
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+    entry_points.add_entry_point(transfer_from_entrypoint);
+    entry_points.add_entry_point(balance_of_entrypoint);
 
-    let (_contract_hash, _version) = storage::new_contract(
+    let (contract_hash, _version) = storage::new_contract(
         entry_points,
         None,
         Some(TEST_SESSION.to_string()),
         None,
     );
+    runtime::put_key(TEST_SESSION_CONTRACT_HASH, contract_hash.into());
 }
@@ -0,0 +1,69 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use casper_contract::{
+    contract_api::{account, runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, ApiError, ContractPackageHash, Key, RuntimeArgs, U128, U256, U512,
+};
+
+const ARG_VE_PACKAGE_HASH: &str = "ve_package_hash";
+const ARG_ENTRY_POINT: &str = "entry_point";
+const ARG_AMOUNT: &str = "amount";
+const ARG_PURSE: &str = "purse";
+const ARG_LOCK_DURATION: &str = "lock_duration";
+const ARG_TO: &str = "to";
+const ARG_TOKEN_ID: &str = "token_id";
+
+/// Session wrapper for escrows installed with `native_lock`: funds a fresh purse from
+/// the account's main purse and hands it to one of the deposit entry points.
+#[no_mangle]
+pub extern "C" fn call() {
+    let ve_package_hash: Key = runtime::get_named_arg(ARG_VE_PACKAGE_HASH);
+    let entry_point: String = runtime::get_named_arg(ARG_ENTRY_POINT);
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+
+    // The escrow only ever sees this purse, never the main purse
+    let purse = system::create_purse();
+    system::transfer_from_purse_to_purse(
+        account::get_main_purse(),
+        purse,
+        U512::from(amount.as_u128()),
+        None,
+    )
+    .unwrap_or_revert();
+
+    let mut args = runtime_args! {
+        ARG_PURSE => purse,
+        ARG_AMOUNT => amount
+    };
+    match entry_point.as_str() {
+        "create_lock" => {
+            let lock_duration: u64 = runtime::get_named_arg(ARG_LOCK_DURATION);
+            args.insert(ARG_LOCK_DURATION, lock_duration).unwrap_or_revert();
+        }
+        "create_lock_for" => {
+            let lock_duration: u64 = runtime::get_named_arg(ARG_LOCK_DURATION);
+            let to: Key = runtime::get_named_arg(ARG_TO);
+            args.insert(ARG_LOCK_DURATION, lock_duration).unwrap_or_revert();
+            args.insert(ARG_TO, to).unwrap_or_revert();
+        }
+        "deposit_for" | "increase_amount" => {
+            let token_id: U256 = runtime::get_named_arg(ARG_TOKEN_ID);
+            args.insert(ARG_TOKEN_ID, token_id).unwrap_or_revert();
+        }
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+
+    let _: () = runtime::call_versioned_contract(
+        ContractPackageHash::new(ve_package_hash.into_hash().unwrap_or_revert()),
+        None,
+        &entry_point,
+        args,
+    );
+}
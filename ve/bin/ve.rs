@@ -11,10 +11,11 @@ use casper_contract::{
 };
 use crate::alloc::string::ToString;
 use casper_types::{
-    runtime_args, CLType, CLTyped, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess,
-    EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+    contracts::NamedKeys, runtime_args, CLType, CLTyped, CLValue, ContractHash,
+    ContractPackageHash, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Group, Key,
+    Parameter, RuntimeArgs, URef, U256,
 };
-use ve::{Meta, TokenId, CEP47, NFTToken, vedata::{self, TOKEN_CONTRACT_HASH, ART_PROXY_CONTRACT_HASH}};
+use ve::{Meta, TokenId, CEP47, NFTToken, error::VeError, lock::{non_reentrant, when_not_paused, PAUSE_TRANSFERS}, roles::{self, METADATA_ADMIN_ROLE}, vedata::{self, TOKEN_CONTRACT_HASH, ART_PROXY_CONTRACT_HASH}, erc20_helpers::TOKEN_STANDARD, native::NATIVE_LOCK};
 
 #[no_mangle]
 fn constructor() {
@@ -23,8 +24,10 @@ fn constructor() {
     let meta = runtime::get_named_arg::<Meta>("meta");
     let token_contract: Key = runtime::get_named_arg(TOKEN_CONTRACT_HASH);
     let art_proxy_contract: Key = runtime::get_named_arg(ART_PROXY_CONTRACT_HASH);
+    let token_standard: u8 = runtime::get_named_arg(TOKEN_STANDARD);
+    let native_lock: bool = runtime::get_named_arg(NATIVE_LOCK);
     NFTToken::default().constructor(name, symbol, meta);
-    vedata::initialize(token_contract, art_proxy_contract);
+    vedata::initialize(token_contract, art_proxy_contract, token_standard, native_lock);
 }
 
 #[no_mangle]
@@ -86,18 +89,27 @@ fn token_meta() {
 fn update_token_meta() {
     let token_id = runtime::get_named_arg::<TokenId>("token_id");
     let token_meta = runtime::get_named_arg::<Meta>("token_meta");
-    NFTToken::default()
-        .set_token_meta(token_id, token_meta)
-        .unwrap_or_revert();
+    non_reentrant(|| {
+        roles::only_role(METADATA_ADMIN_ROLE, VeError::MissingRole);
+        NFTToken::default()
+            .set_token_meta(token_id, token_meta)
+            .unwrap_or_revert();
+    });
 }
 
 #[no_mangle]
 fn transfer() {
     let recipient = runtime::get_named_arg::<Key>("recipient");
     let token_ids = runtime::get_named_arg::<Vec<TokenId>>("token_ids");
-    NFTToken::default()
-        .transfer(recipient, token_ids)
-        .unwrap_or_revert();
+    when_not_paused(PAUSE_TRANSFERS, VeError::TransfersPaused);
+    non_reentrant(|| {
+        for token_id in &token_ids {
+            vedata::_transfer_delegates(token_id.as_u64(), recipient);
+        }
+        NFTToken::default()
+            .transfer(recipient, token_ids)
+            .unwrap_or_revert();
+    });
 }
 
 #[no_mangle]
@@ -105,18 +117,26 @@ fn transfer_from() {
     let sender = runtime::get_named_arg::<Key>("sender");
     let recipient = runtime::get_named_arg::<Key>("recipient");
     let token_ids = runtime::get_named_arg::<Vec<TokenId>>("token_ids");
-    NFTToken::default()
-        .transfer_from(sender, recipient, token_ids)
-        .unwrap_or_revert();
+    when_not_paused(PAUSE_TRANSFERS, VeError::TransfersPaused);
+    non_reentrant(|| {
+        for token_id in &token_ids {
+            vedata::_transfer_delegates(token_id.as_u64(), recipient);
+        }
+        NFTToken::default()
+            .transfer_from(sender, recipient, token_ids)
+            .unwrap_or_revert();
+    });
 }
 
 #[no_mangle]
 fn approve() {
     let spender = runtime::get_named_arg::<Key>("spender");
     let token_ids = runtime::get_named_arg::<Vec<TokenId>>("token_ids");
-    NFTToken::default()
-        .approve(spender, token_ids)
-        .unwrap_or_revert();
+    non_reentrant(|| {
+        NFTToken::default()
+            .approve(spender, token_ids)
+            .unwrap_or_revert();
+    });
 }
 
 #[no_mangle]
@@ -129,14 +149,26 @@ fn get_approved() {
 
 #[no_mangle]
 fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+
+    // An existing package means this session upgrades the installed contract
+    match runtime::get_key(&format!("{}_contract_package_hash", contract_name)) {
+        Some(package_key) => upgrade(&contract_name, package_key),
+        None => install(&contract_name),
+    }
+}
+
+fn install(contract_name: &str) {
     // Read arguments for the constructor call.
     let name: String = runtime::get_named_arg("name");
     let symbol: String = runtime::get_named_arg("symbol");
     let meta: Meta = runtime::get_named_arg("meta");
     let token_contract: Key = runtime::get_named_arg(TOKEN_CONTRACT_HASH);
     let art_proxy_contract: Key = runtime::get_named_arg(ART_PROXY_CONTRACT_HASH);
-
-    let contract_name: String = runtime::get_named_arg("contract_name");
+    // 0 for the ERC-20 example, 1 for CEP-18
+    let token_standard: u8 = runtime::get_named_arg(TOKEN_STANDARD);
+    // Lock native CSPR instead of the token, deposits then come with a source purse
+    let native_lock: bool = runtime::get_named_arg(NATIVE_LOCK);
 
     // Prepare constructor args
     let constructor_args = runtime_args! {
@@ -144,14 +176,16 @@ fn call() {
         "symbol" => symbol,
         "meta" => meta,
         TOKEN_CONTRACT_HASH => token_contract,
-        ART_PROXY_CONTRACT_HASH => art_proxy_contract
+        ART_PROXY_CONTRACT_HASH => art_proxy_contract,
+        TOKEN_STANDARD => token_standard,
+        NATIVE_LOCK => native_lock
     };
 
     let (contract_hash, _) = storage::new_contract(
         get_entry_points(),
         None,
         Some(format!("{}_contract_package_hash", contract_name)),
-        None,
+        Some(format!("{}_contract_package_access", contract_name)),
     );
 
     let package_hash: ContractPackageHash = ContractPackageHash::new(
@@ -174,6 +208,32 @@ fn call() {
     storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
         .unwrap_or_revert();
 
+    put_contract_hash_keys(contract_name, contract_hash);
+}
+
+fn upgrade(contract_name: &str, package_key: Key) {
+    let package_hash = ContractPackageHash::new(package_key.into_hash().unwrap_or_revert());
+
+    // Packages installed before upgrades were supported never stored their access uref
+    runtime::get_key(&format!("{}_contract_package_access", contract_name))
+        .unwrap_or_revert_with(VeError::InvalidAccessKeyName);
+
+    let previous_contract_hash = runtime::get_key(&format!("{}_contract_hash", contract_name))
+        .and_then(|key| key.into_hash())
+        .map(ContractHash::new)
+        .unwrap_or_revert_with(VeError::MissingPackageHashForUpgrade);
+
+    // Named keys of the previous version are carried over to the new one
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), NamedKeys::new());
+    storage::disable_contract_version(package_hash, previous_contract_hash).unwrap_or_revert();
+
+    let _: () = runtime::call_contract(contract_hash, "migrate", runtime_args! {});
+
+    put_contract_hash_keys(contract_name, contract_hash);
+}
+
+fn put_contract_hash_keys(contract_name: &str, contract_hash: ContractHash) {
     runtime::put_key(
         &format!("{}_contract_hash", contract_name),
         contract_hash.into(),
@@ -193,7 +253,9 @@ fn get_entry_points() -> EntryPoints {
             Parameter::new("symbol", String::cl_type()),
             Parameter::new("meta", Meta::cl_type()),
             Parameter::new(TOKEN_CONTRACT_HASH, Key::cl_type()),
-            Parameter::new(ART_PROXY_CONTRACT_HASH, Key::cl_type())
+            Parameter::new(ART_PROXY_CONTRACT_HASH, Key::cl_type()),
+            Parameter::new(TOKEN_STANDARD, u8::cl_type()),
+            Parameter::new(NATIVE_LOCK, bool::cl_type())
         ],
         <()>::cl_type(),
         EntryPointAccess::Groups(vec![Group::new("constructor")]),
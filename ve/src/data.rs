@@ -11,7 +11,7 @@ use casper_types::{system::CallStackElement, ContractPackageHash, Key, URef, U25
 use crate::utils::{get_key, key_and_value_to_str, key_to_str, set_key};
 use crate::dict::Dict;
 
-use crate::{event::CEP47Event, Meta, TokenId};
+use crate::{event::{CEP47Event, VeEvent}, Meta, TokenId};
 
 const BALANCES_DICT: &str = "balances";
 pub const ALLOWANCES_DICT: &str = "allowances";
@@ -311,3 +311,141 @@ pub fn emit(event: &CEP47Event) {
         let _: URef = storage::new_uref(param);
     }
 }
+
+pub fn emit_ve(event: &VeEvent) {
+    let mut param = BTreeMap::new();
+    param.insert(CONTRACT_PACKAGE_HASH, contract_package_hash().to_string());
+    match event {
+        VeEvent::RoleGranted {
+            role,
+            account,
+            sender,
+        } => {
+            param.insert("event_type", "ve_role_granted".to_string());
+            param.insert("role", role.clone());
+            param.insert("account", account.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::RoleRevoked {
+            role,
+            account,
+            sender,
+        } => {
+            param.insert("event_type", "ve_role_revoked".to_string());
+            param.insert("role", role.clone());
+            param.insert("account", account.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::RoleTransferProposed { role, from, to } => {
+            param.insert("event_type", "ve_role_transfer_proposed".to_string());
+            param.insert("role", role.clone());
+            param.insert("from", from.to_string());
+            param.insert("to", to.to_string());
+        }
+        VeEvent::RoleTransferAccepted { role, from, to } => {
+            param.insert("event_type", "ve_role_transfer_accepted".to_string());
+            param.insert("role", role.clone());
+            param.insert("from", from.to_string());
+            param.insert("to", to.to_string());
+        }
+        VeEvent::RoleTransferCancelled { role, from, to } => {
+            param.insert("event_type", "ve_role_transfer_cancelled".to_string());
+            param.insert("role", role.clone());
+            param.insert("from", from.to_string());
+            param.insert("to", to.to_string());
+        }
+        VeEvent::Paused { flags, sender } => {
+            param.insert("event_type", "ve_paused".to_string());
+            param.insert("flags", flags.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::Unpaused { flags, sender } => {
+            param.insert("event_type", "ve_unpaused".to_string());
+            param.insert("flags", flags.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::OperationQueued {
+            op_id,
+            kind,
+            eta,
+            proposer,
+        } => {
+            param.insert("event_type", "ve_operation_queued".to_string());
+            param.insert("op_id", op_id.to_string());
+            param.insert("kind", kind.to_string());
+            param.insert("eta", eta.to_string());
+            param.insert("proposer", proposer.to_string());
+        }
+        VeEvent::OperationExecuted {
+            op_id,
+            kind,
+            sender,
+        } => {
+            param.insert("event_type", "ve_operation_executed".to_string());
+            param.insert("op_id", op_id.to_string());
+            param.insert("kind", kind.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::OperationCancelled {
+            op_id,
+            kind,
+            sender,
+        } => {
+            param.insert("event_type", "ve_operation_cancelled".to_string());
+            param.insert("op_id", op_id.to_string());
+            param.insert("kind", kind.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::TokenRescued {
+            token,
+            recipient,
+            amount,
+            sender,
+        } => {
+            param.insert("event_type", "ve_token_rescued".to_string());
+            param.insert("token", token.to_string());
+            param.insert("recipient", recipient.to_string());
+            param.insert("amount", amount.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::CreateManaged {
+            to,
+            managed_id,
+            sender,
+        } => {
+            param.insert("event_type", "ve_create_managed".to_string());
+            param.insert("to", to.to_string());
+            param.insert("managed_id", managed_id.to_string());
+            param.insert("sender", sender.to_string());
+        }
+        VeEvent::DepositManaged {
+            owner,
+            token_id,
+            managed_id,
+            amount,
+            shares,
+        } => {
+            param.insert("event_type", "ve_deposit_managed".to_string());
+            param.insert("owner", owner.to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("managed_id", managed_id.to_string());
+            param.insert("amount", amount.to_string());
+            param.insert("shares", shares.to_string());
+        }
+        VeEvent::WithdrawManaged {
+            owner,
+            token_id,
+            managed_id,
+            amount,
+            shares,
+        } => {
+            param.insert("event_type", "ve_withdraw_managed".to_string());
+            param.insert("owner", owner.to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("managed_id", managed_id.to_string());
+            param.insert("amount", amount.to_string());
+            param.insert("shares", shares.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
@@ -1,54 +1,105 @@
 use casper_contract::{
-    contract_api::{runtime}
+    contract_api::{runtime},
+    unwrap_or_revert::UnwrapOrRevert,
 };
 
 use casper_types::{
+    bytesrepr::FromBytes,
+    CLTyped,
     ContractHash,
+    ContractPackageHash,
     runtime_args,
     RuntimeArgs,
     Key,
     U256
 };
 
+use crate::error::VeError;
+use crate::utils::{get_key, require};
+
+pub const TOKEN_STANDARD: &str = "token_standard";
+
+/// Fungible token interfaces the escrow can talk to, picked at install. Both name their
+/// entry point arguments the same way and the ERC-20 example's `Address` values share
+/// their encoding and CLType with `Key`, so only the way the token is addressed differs.
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum TokenStandard {
+    /// casper-ecosystem ERC-20 example, called by contract hash
+    Erc20 = 0,
+    /// CEP-18, called by package hash so token upgrades are picked up
+    Cep18 = 1,
+}
+
+impl TokenStandard {
+    pub fn from_u8(value: u8) -> Option<TokenStandard> {
+        match value {
+            0 => Some(TokenStandard::Erc20),
+            1 => Some(TokenStandard::Cep18),
+            _ => None,
+        }
+    }
+}
+
+pub fn token_standard() -> TokenStandard {
+    let value: u8 = get_key(TOKEN_STANDARD).unwrap_or(TokenStandard::Erc20 as u8);
+    TokenStandard::from_u8(value).unwrap_or_revert_with(VeError::InvalidTokenStandard)
+}
+
+fn call_token<T: CLTyped + FromBytes>(token: Key, entry_point: &str, args: RuntimeArgs) -> T {
+    let hash = token.into_hash().unwrap_or_revert();
+    match token_standard() {
+        TokenStandard::Erc20 => runtime::call_contract(ContractHash::new(hash), entry_point, args),
+        TokenStandard::Cep18 => runtime::call_versioned_contract(
+            ContractPackageHash::new(hash),
+            None,
+            entry_point,
+            args,
+        ),
+    }
+}
+
+/// Token amounts are U256, escrow accounting is u128
+pub fn to_u128(amount: U256) -> u128 {
+    require(amount <= U256::from(u128::MAX), VeError::TokenAmountOverflow);
+    amount.as_u128()
+}
+
 pub fn get_total_supply(contract: Key) -> u128 {
-    let total_supply : U256 = runtime::call_contract(
-        ContractHash::new(contract.into_hash().unwrap()),
-        "total_supply",
-        runtime_args! {},
-    );
-    total_supply.as_u128()
+    let total_supply: U256 = call_token(contract, "total_supply", runtime_args! {});
+    to_u128(total_supply)
 }
 
 pub fn get_balance(token: Key, user: Key) -> u128 {
-    let b : U256 = runtime::call_contract(
-        ContractHash::new(token.into_hash().unwrap()),
+    let b: U256 = call_token(
+        token,
         "balance_of",
         runtime_args! {
             "address" => user
         },
     );
-    b.as_u128()
+    to_u128(b)
 }
 
 pub fn transfer(contract_hash: Key, recipient: Key, amount: u128) {
-    let _: () = runtime::call_contract(
-        ContractHash::new(contract_hash.into_hash().unwrap()),
+    let _: () = call_token(
+        contract_hash,
         "transfer",
         runtime_args! {
             "recipient" => recipient,
             "amount" => U256::from(amount)
-        }
+        },
     );
 }
 
 pub fn transfer_from(contract_hash: Key, from: Key, recipient: Key, amount: u128) {
-    let _: () = runtime::call_contract(
-        ContractHash::new(contract_hash.into_hash().unwrap()),
+    let _: () = call_token(
+        contract_hash,
         "transfer_from",
         runtime_args! {
             "owner" => from,
             "recipient" => recipient,
             "amount" => U256::from(amount)
-        }
+        },
     );
-}
\ No newline at end of file
+}
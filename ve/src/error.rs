@@ -162,7 +162,46 @@ pub enum VeError {
     InvalidBlock = 157,
     NotVoter = 158,
     FromMustNotTo = 159,
-    TooManyTokenIds = 160
+    TooManyTokenIds = 160,
+    BatchTooLarge = 161,
+    InvalidCurveRange = 162,
+    TooManySamples = 163,
+    CheckpointRequired = 164,
+    InvalidCheckpointBudget = 165,
+    MissingRole = 166,
+    UnknownRole = 167,
+    NoPendingTransfer = 168,
+    NotPendingMember = 169,
+    LocksPaused = 170,
+    DepositsPaused = 171,
+    MergesPaused = 172,
+    DelegationPaused = 173,
+    TransfersPaused = 174,
+    SmartWalletNotAllowed = 175,
+    InvalidTimelockDelay = 176,
+    UnknownOperation = 177,
+    OperationNotQueued = 178,
+    OperationNotReady = 179,
+    OperationExpired = 180,
+    UnknownOperationKind = 181,
+    RescueExceedsSurplus = 182,
+    TransferAmountMismatch = 183,
+    TokenAmountOverflow = 184,
+    InvalidTokenStandard = 185,
+    AttachedOrVoted = 186,
+    LockNotExpired = 187,
+    NativeRecipientNotAccount = 188,
+    AssetNotAccepted = 189,
+    AssetMismatch = 190,
+    InvalidAssetWeight = 191,
+    NotManagedNFT = 192,
+    NotNormalNFT = 193,
+    NotLockedNFT = 194,
+    LockPermanent = 195,
+    ZeroShares = 196,
+    PrivilegedRole = 197,
+    NotTeamOrVoter = 198,
+    InvalidSmartWalletChecker = 199
 }
 
 impl From<VeError> for ApiError {
@@ -1,4 +1,4 @@
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 use casper_types::Key;
 
 use crate::TokenId;
@@ -26,3 +26,80 @@ pub enum CEP47Event {
         token_id: TokenId,
     },
 }
+
+pub enum VeEvent {
+    RoleGranted {
+        role: String,
+        account: Key,
+        sender: Key,
+    },
+    RoleRevoked {
+        role: String,
+        account: Key,
+        sender: Key,
+    },
+    RoleTransferProposed {
+        role: String,
+        from: Key,
+        to: Key,
+    },
+    RoleTransferAccepted {
+        role: String,
+        from: Key,
+        to: Key,
+    },
+    RoleTransferCancelled {
+        role: String,
+        from: Key,
+        to: Key,
+    },
+    Paused {
+        flags: u8,
+        sender: Key,
+    },
+    Unpaused {
+        flags: u8,
+        sender: Key,
+    },
+    OperationQueued {
+        op_id: u64,
+        kind: u8,
+        eta: u64,
+        proposer: Key,
+    },
+    OperationExecuted {
+        op_id: u64,
+        kind: u8,
+        sender: Key,
+    },
+    OperationCancelled {
+        op_id: u64,
+        kind: u8,
+        sender: Key,
+    },
+    TokenRescued {
+        token: Key,
+        recipient: Key,
+        amount: u128,
+        sender: Key,
+    },
+    CreateManaged {
+        to: Key,
+        managed_id: u64,
+        sender: Key,
+    },
+    DepositManaged {
+        owner: Key,
+        token_id: u64,
+        managed_id: u64,
+        amount: u128,
+        shares: u128,
+    },
+    WithdrawManaged {
+        owner: Key,
+        token_id: u64,
+        managed_id: u64,
+        amount: u128,
+        shares: u128,
+    },
+}
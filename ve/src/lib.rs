@@ -11,7 +11,11 @@ pub mod error;
 pub mod utils;
 pub mod erc20_helpers;
 pub mod lock;
+pub mod native;
 pub mod dict;
+pub mod roles;
+pub mod smart_wallet;
+pub mod timelock;
 
 pub use cep47::{Error, CEP47, NFTToken};
 
@@ -1,7 +1,16 @@
-use crate::{error::VeError, utils::require, vedata::IS_LOCKED};
+use crate::{error::VeError, utils::require, vedata::{IS_LOCKED, PAUSED_FLAGS}};
 use casper_contract::contract_api::{runtime, storage};
 use crate::utils::{get_key, set_key};
 
+// Operation classes that can be paused independently
+pub const PAUSE_LOCKS: u8 = 1;
+pub const PAUSE_DEPOSITS: u8 = 1 << 1;
+pub const PAUSE_MERGES: u8 = 1 << 2;
+pub const PAUSE_DELEGATION: u8 = 1 << 3;
+pub const PAUSE_TRANSFERS: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_LOCKS | PAUSE_DEPOSITS | PAUSE_MERGES | PAUSE_DELEGATION | PAUSE_TRANSFERS;
+
 pub fn when_not_locked() {
     let locked: bool = get_key(IS_LOCKED).unwrap();
     require(!locked, VeError::ContractLocked);
@@ -15,9 +24,36 @@ pub fn unlock_contract() {
     set_key(IS_LOCKED, false);
 }
 
+/// Runs `f` with the contract locked. Any guarded entry point reached again before
+/// `f` returns, e.g. from a token callback, reverts with `ContractLocked`.
+pub fn non_reentrant<T, F: FnOnce() -> T>(f: F) -> T {
+    when_not_locked();
+    lock_contract();
+    let ret = f();
+    unlock_contract();
+    ret
+}
+
+pub fn paused_flags() -> u8 {
+    get_key(PAUSED_FLAGS).unwrap_or(0)
+}
+
+pub fn set_paused_flags(flags: u8) {
+    set_key(PAUSED_FLAGS, flags & PAUSE_ALL);
+}
+
+/// Reverts with `e` while the operation class `flag` is paused.
+pub fn when_not_paused(flag: u8, e: VeError) {
+    require(paused_flags() & flag == 0, e);
+}
+
 pub fn init() {
     runtime::put_key(
         IS_LOCKED,
         storage::new_uref(false).into(),
     );
-}
\ No newline at end of file
+    runtime::put_key(
+        PAUSED_FLAGS,
+        storage::new_uref(0u8).into(),
+    );
+}
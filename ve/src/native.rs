@@ -0,0 +1,57 @@
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{Key, URef, U512};
+
+use crate::error::VeError;
+use crate::utils::{get_key, require, set_key};
+
+pub const NATIVE_LOCK: &str = "native_lock";
+pub const ESCROW_PURSE: &str = "escrow_purse";
+pub const ARG_PURSE: &str = "purse";
+
+/// @notice Native mode locks CSPR held in a contract-owned purse instead of a token
+pub fn init(enabled: bool) {
+    set_key(NATIVE_LOCK, enabled);
+    if enabled {
+        let purse = system::create_purse();
+        runtime::put_key(ESCROW_PURSE, purse.into());
+    }
+}
+
+pub fn is_enabled() -> bool {
+    get_key(NATIVE_LOCK).unwrap_or(false)
+}
+
+fn escrow_purse() -> URef {
+    runtime::get_key(ESCROW_PURSE)
+        .and_then(Key::into_uref)
+        .unwrap_or_revert_with(VeError::MissingStorageUref)
+}
+
+fn to_u128(motes: U512) -> u128 {
+    require(motes <= U512::from(u128::MAX), VeError::TokenAmountOverflow);
+    motes.as_u128()
+}
+
+pub fn balance() -> u128 {
+    to_u128(system::get_purse_balance(escrow_purse()).unwrap_or_revert())
+}
+
+/// @notice Move `amount` motes from the `purse` argument of the current call into escrow
+/// @dev The purse is handed over by a session wrapper, see bin/lock_cspr.rs
+pub fn transfer_in(amount: u128) {
+    let source: URef = runtime::get_named_arg(ARG_PURSE);
+    system::transfer_from_purse_to_purse(source, escrow_purse(), U512::from(amount), None)
+        .unwrap_or_revert();
+}
+
+/// @notice Pay `amount` motes out of escrow, only accounts can receive CSPR
+pub fn transfer_out(recipient: Key, amount: u128) {
+    let account = recipient
+        .into_account()
+        .unwrap_or_revert_with(VeError::NativeRecipientNotAccount);
+    system::transfer_from_purse_to_account(escrow_purse(), account, U512::from(amount), None)
+        .unwrap_or_revert();
+}
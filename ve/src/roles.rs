@@ -0,0 +1,168 @@
+use alloc::string::{String, ToString};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::Key;
+
+use crate::data;
+use crate::dict::Dict;
+use crate::error::VeError;
+use crate::event::VeEvent;
+use crate::utils::{self, require};
+
+pub const ROLES: &str = "roles";
+
+pub const TEAM_ROLE: &str = "team";
+pub const VOTER_ROLE: &str = "voter";
+pub const PAUSER_ROLE: &str = "pauser";
+pub const METADATA_ADMIN_ROLE: &str = "metadata_admin";
+pub const MIGRATOR_ROLE: &str = "migrator";
+
+pub const ALL_ROLES: [&str; 5] = [
+    TEAM_ROLE,
+    VOTER_ROLE,
+    PAUSER_ROLE,
+    METADATA_ADMIN_ROLE,
+    MIGRATOR_ROLE,
+];
+
+/// Creates the role dictionary and hands every role to `admin`.
+pub fn init(admin: Key) {
+    Dict::init(ROLES);
+    for role in ALL_ROLES.iter() {
+        grant(role, admin, admin);
+        set_holder(role, admin);
+    }
+}
+
+/// Roles that can take over the escrow, they only change through the timelock.
+pub const PRIVILEGED_ROLES: [&str; 3] = [TEAM_ROLE, VOTER_ROLE, MIGRATOR_ROLE];
+
+pub fn is_known(role: &str) -> bool {
+    ALL_ROLES.contains(&role)
+}
+
+pub fn is_privileged(role: &str) -> bool {
+    PRIVILEGED_ROLES.contains(&role)
+}
+
+/// Position of `role` in `ALL_ROLES`, how queued operations refer to a role.
+pub fn index_of(role: &str) -> Option<u64> {
+    ALL_ROLES.iter().position(|known| *known == role).map(|index| index as u64)
+}
+
+pub fn at_index(index: u64) -> Option<&'static str> {
+    ALL_ROLES.get(index as usize).copied()
+}
+
+fn member_key(role: &str, account: &Key) -> String {
+    utils::key_and_value_to_str(account, &role.to_string())
+}
+
+pub fn has_role(role: &str, account: Key) -> bool {
+    Dict::instance(ROLES)
+        .get(&member_key(role, &account))
+        .unwrap_or(false)
+}
+
+/// Reverts with `err` unless the immediate caller holds `role`.
+pub fn only_role(role: &str, err: VeError) {
+    let caller = utils::get_immediate_caller_key();
+    require(has_role(role, caller), err);
+}
+
+pub fn grant(role: &str, account: Key, sender: Key) {
+    if has_role(role, account) {
+        return;
+    }
+    Dict::instance(ROLES).set(&member_key(role, &account), true);
+    data::emit_ve(&VeEvent::RoleGranted {
+        role: role.to_string(),
+        account,
+        sender,
+    });
+}
+
+/// Member that `role` was last handed to, stored under the `<role>_holder` named key.
+/// Escrows upgraded from before it was tracked learn it at their next hand-over.
+fn holder_name(role: &str) -> String {
+    format!("{}_holder", role)
+}
+
+pub fn holder(role: &str) -> Option<Key> {
+    utils::get_key::<Key>(&holder_name(role)).filter(|holder| has_role(role, *holder))
+}
+
+pub fn set_holder(role: &str, account: Key) {
+    utils::set_key(&holder_name(role), account);
+}
+
+/// Pending hand-over of `role`, stored under the `pending_<role>` named key as
+/// (proposed member, member being replaced or null key).
+fn pending_name(role: &str) -> String {
+    format!("pending_{}", role)
+}
+
+pub fn pending_transfer(role: &str) -> Option<(Key, Key)> {
+    utils::get_key::<Option<(Key, Key)>>(&pending_name(role)).unwrap_or(None)
+}
+
+/// Starts handing `role` over to `to`. The caller is replaced once `to` accepts
+/// if it holds the role itself, otherwise the current holder is.
+pub fn propose_transfer(role: &str, to: Key) {
+    propose_transfer_as(role, utils::get_immediate_caller_key(), to);
+}
+
+/// @notice Like `propose_transfer`, on behalf of `proposer` instead of the caller
+pub fn propose_transfer_as(role: &str, proposer: Key, to: Key) {
+    let from = if has_role(role, proposer) {
+        proposer
+    } else {
+        holder(role).unwrap_or_else(utils::null_key)
+    };
+    utils::set_key(&pending_name(role), Some((to, from)));
+    data::emit_ve(&VeEvent::RoleTransferProposed {
+        role: role.to_string(),
+        from,
+        to,
+    });
+}
+
+/// Completes the pending hand-over of `role`; only the proposed member may accept.
+pub fn accept_transfer(role: &str) {
+    let caller = utils::get_immediate_caller_key();
+    let (to, from) = pending_transfer(role).unwrap_or_revert_with(VeError::NoPendingTransfer);
+    require(caller == to, VeError::NotPendingMember);
+
+    utils::set_key(&pending_name(role), Option::<(Key, Key)>::None);
+    grant(role, to, from);
+    set_holder(role, to);
+    if utils::is_not_null(from) && from != to {
+        revoke(role, from, to);
+    }
+    data::emit_ve(&VeEvent::RoleTransferAccepted {
+        role: role.to_string(),
+        from,
+        to,
+    });
+}
+
+pub fn cancel_transfer(role: &str) {
+    let (to, from) = pending_transfer(role).unwrap_or_revert_with(VeError::NoPendingTransfer);
+    utils::set_key(&pending_name(role), Option::<(Key, Key)>::None);
+    data::emit_ve(&VeEvent::RoleTransferCancelled {
+        role: role.to_string(),
+        from,
+        to,
+    });
+}
+
+pub fn revoke(role: &str, account: Key, sender: Key) {
+    if !has_role(role, account) {
+        return;
+    }
+    Dict::instance(ROLES).remove::<bool>(&member_key(role, &account));
+    data::emit_ve(&VeEvent::RoleRevoked {
+        role: role.to_string(),
+        account,
+        sender,
+    });
+}
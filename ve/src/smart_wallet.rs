@@ -0,0 +1,81 @@
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs};
+
+use crate::dict::Dict;
+use crate::error::VeError;
+use crate::utils::{self, get_key, require, set_key};
+
+pub const SMART_WALLET_CHECK_ENABLED: &str = "smart_wallet_check_enabled";
+pub const SMART_WALLET_ALLOWLIST: &str = "smart_wallet_allowlist";
+pub const SMART_WALLET_CHECKER: &str = "smart_wallet_checker";
+
+pub fn init() {
+    Dict::init(SMART_WALLET_ALLOWLIST);
+    set_key(SMART_WALLET_CHECK_ENABLED, false);
+    set_key(SMART_WALLET_CHECKER, Option::<Key>::None);
+}
+
+pub fn is_enabled() -> bool {
+    get_key(SMART_WALLET_CHECK_ENABLED).unwrap_or(false)
+}
+
+pub fn set_enabled(enabled: bool) {
+    set_key(SMART_WALLET_CHECK_ENABLED, enabled);
+}
+
+pub fn checker() -> Option<Key> {
+    get_key::<Option<Key>>(SMART_WALLET_CHECKER).unwrap_or(None)
+}
+
+/// The checker is called by contract hash, other keys are refused up front
+pub fn set_checker(checker: Option<Key>) {
+    if let Some(checker) = checker {
+        require(
+            checker.into_hash().is_some(),
+            VeError::InvalidSmartWalletChecker,
+        );
+    }
+    set_key(SMART_WALLET_CHECKER, checker);
+}
+
+pub fn set_allowed(address: Key, allowed: bool) {
+    let dict = Dict::instance(SMART_WALLET_ALLOWLIST);
+    if allowed {
+        dict.set_by_key(&address, true);
+    } else {
+        dict.remove_by_key::<bool>(&address);
+    }
+}
+
+/// Accounts are always allowed. Contracts are allowed while the check is disabled,
+/// when they are on the allowlist, or when the checker contract approves them.
+pub fn is_allowed(address: Key) -> bool {
+    if address.into_account().is_some() || !is_enabled() {
+        return true;
+    }
+    let dict = Dict::instance(SMART_WALLET_ALLOWLIST);
+    if dict.get_by_key(&address).unwrap_or(false) {
+        return true;
+    }
+    match checker() {
+        Some(checker) => runtime::call_contract(
+            ContractHash::new(
+                checker
+                    .into_hash()
+                    .unwrap_or_revert_with(VeError::InvalidSmartWalletChecker),
+            ),
+            "check",
+            runtime_args! {
+                "address" => address
+            },
+        ),
+        None => false,
+    }
+}
+
+pub fn assert_allowed_caller() {
+    require(
+        is_allowed(utils::get_immediate_caller_key()),
+        VeError::SmartWalletNotAllowed,
+    );
+}
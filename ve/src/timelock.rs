@@ -0,0 +1,193 @@
+use alloc::{string::ToString, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, Key,
+};
+
+use crate::data;
+use crate::dict::Dict;
+use crate::error::VeError;
+use crate::event::VeEvent;
+use crate::utils::{self, current_block_timestamp_seconds, get_key, require, set_key};
+
+pub const QUEUED_OPERATIONS: &str = "queued_operations";
+pub const NEXT_OPERATION_ID: &str = "next_operation_id";
+pub const TIMELOCK_DELAY: &str = "timelock_delay";
+
+pub const MIN_TIMELOCK_DELAY: u64 = 24 * 60 * 60;
+pub const DEFAULT_TIMELOCK_DELAY: u64 = 2 * 24 * 60 * 60;
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+/// Queued operations can only be executed during this window after their eta
+pub const GRACE_PERIOD: u64 = 14 * 24 * 60 * 60;
+
+pub const OP_SET_ART_PROXY: u8 = 0;
+pub const OP_PROPOSE_TEAM: u8 = 1;
+pub const OP_PROPOSE_VOTER: u8 = 2;
+pub const OP_SET_CHECKPOINT_MAX_WEEKS: u8 = 3;
+pub const OP_SET_TIMELOCK_DELAY: u8 = 4;
+pub const OP_SET_STRICT_TRANSFERS: u8 = 5;
+pub const OP_SET_ASSET_WEIGHT: u8 = 6;
+pub const OP_GRANT_ROLE: u8 = 7;
+pub const OP_REVOKE_ROLE: u8 = 8;
+
+pub const STATUS_QUEUED: u8 = 0;
+pub const STATUS_EXECUTED: u8 = 1;
+pub const STATUS_CANCELLED: u8 = 2;
+
+/// An admin action waiting for its eta. Depending on `kind` either `target` or
+/// `value` carries the new setting, the other one is left at its default.
+#[derive(Clone)]
+pub struct QueuedOperation {
+    pub kind: u8,
+    pub target: Key,
+    pub value: u64,
+    pub eta: u64,
+    pub proposer: Key,
+    pub status: u8,
+}
+
+impl ToBytes for QueuedOperation {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend(self.kind.to_bytes()?);
+        result.extend(self.target.to_bytes()?);
+        result.extend(self.value.to_bytes()?);
+        result.extend(self.eta.to_bytes()?);
+        result.extend(self.proposer.to_bytes()?);
+        result.extend(self.status.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.kind.serialized_length()
+            + self.target.serialized_length()
+            + self.value.serialized_length()
+            + self.eta.serialized_length()
+            + self.proposer.serialized_length()
+            + self.status.serialized_length()
+    }
+}
+
+impl FromBytes for QueuedOperation {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (kind, remainder) = u8::from_bytes(bytes)?;
+        let (target, remainder) = Key::from_bytes(remainder)?;
+        let (value, remainder) = u64::from_bytes(remainder)?;
+        let (eta, remainder) = u64::from_bytes(remainder)?;
+        let (proposer, remainder) = Key::from_bytes(remainder)?;
+        let (status, remainder) = u8::from_bytes(remainder)?;
+        Ok((
+            QueuedOperation {
+                kind,
+                target,
+                value,
+                eta,
+                proposer,
+                status,
+            },
+            remainder,
+        ))
+    }
+}
+
+impl CLTyped for QueuedOperation {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+pub fn init() {
+    Dict::init(QUEUED_OPERATIONS);
+    set_key(NEXT_OPERATION_ID, 0u64);
+    set_key(TIMELOCK_DELAY, DEFAULT_TIMELOCK_DELAY);
+}
+
+pub fn delay() -> u64 {
+    get_key(TIMELOCK_DELAY).unwrap_or(DEFAULT_TIMELOCK_DELAY)
+}
+
+pub fn set_delay(delay: u64) {
+    require(
+        (MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&delay),
+        VeError::InvalidTimelockDelay,
+    );
+    set_key(TIMELOCK_DELAY, delay);
+}
+
+pub fn next_operation_id() -> u64 {
+    get_key(NEXT_OPERATION_ID).unwrap_or(0)
+}
+
+pub fn get(op_id: u64) -> Option<QueuedOperation> {
+    Dict::instance(QUEUED_OPERATIONS).get(&op_id.to_string())
+}
+
+fn store(op_id: u64, op: QueuedOperation) {
+    Dict::instance(QUEUED_OPERATIONS).set(&op_id.to_string(), op);
+}
+
+/// @notice Queue an operation that can be executed once the current delay has passed
+/// @return The id of the queued operation
+pub fn queue(kind: u8, target: Key, value: u64) -> u64 {
+    let op_id = next_operation_id();
+    let proposer = utils::get_immediate_caller_key();
+    let eta = current_block_timestamp_seconds() + delay();
+    store(
+        op_id,
+        QueuedOperation {
+            kind,
+            target,
+            value,
+            eta,
+            proposer,
+            status: STATUS_QUEUED,
+        },
+    );
+    set_key(NEXT_OPERATION_ID, op_id + 1);
+    data::emit_ve(&VeEvent::OperationQueued {
+        op_id,
+        kind,
+        eta,
+        proposer,
+    });
+    op_id
+}
+
+/// @notice Mark a queued operation as executed, the caller applies its effect
+pub fn take_ready(op_id: u64) -> QueuedOperation {
+    let mut op = get(op_id).unwrap_or_revert_with(VeError::UnknownOperation);
+    require(op.status == STATUS_QUEUED, VeError::OperationNotQueued);
+    let now = current_block_timestamp_seconds();
+    require(now >= op.eta, VeError::OperationNotReady);
+    require(now <= op.eta + GRACE_PERIOD, VeError::OperationExpired);
+    op.status = STATUS_EXECUTED;
+    store(op_id, op.clone());
+    data::emit_ve(&VeEvent::OperationExecuted {
+        op_id,
+        kind: op.kind,
+        sender: utils::get_immediate_caller_key(),
+    });
+    op
+}
+
+pub fn cancel(op_id: u64) {
+    let mut op = get(op_id).unwrap_or_revert_with(VeError::UnknownOperation);
+    require(op.status == STATUS_QUEUED, VeError::OperationNotQueued);
+    op.status = STATUS_CANCELLED;
+    let kind = op.kind;
+    store(op_id, op);
+    data::emit_ve(&VeEvent::OperationCancelled {
+        op_id,
+        kind,
+        sender: utils::get_immediate_caller_key(),
+    });
+}
+
+/// @notice Operations in id order starting at `offset`
+pub fn operations(offset: u64, limit: u64) -> Vec<(u64, QueuedOperation)> {
+    let end = next_operation_id().min(offset.saturating_add(limit));
+    (offset..end)
+        .filter_map(|op_id| get(op_id).map(|op| (op_id, op)))
+        .collect()
+}
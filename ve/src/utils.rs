@@ -9,6 +9,10 @@ use casper_contract::{
 use casper_types::{Key, account::AccountHash, bytesrepr::{FromBytes, ToBytes}, CLTyped, ApiError};
 use casper_types::{system::CallStackElement};
 
+pub fn current_block_timestamp_seconds() -> u64 {
+    u64::from(runtime::get_blocktime()).checked_rem(u64::MAX).unwrap() / 1000
+}
+
 pub fn get_key<T: FromBytes + CLTyped>(name: &str) -> Option<T> {
     match runtime::get_key(name) {
         None => None,
@@ -82,8 +86,7 @@ pub fn require(v: bool, e: VeError) {
 }
 
 pub fn is_null(k: Key) -> bool {
-    let null_bytes: [u8; 32] = vec![0u8; 32].try_into().unwrap();
-    k.to_bytes().unwrap() == null_bytes
+    k == null_key()
 }
 
 pub fn null_key() -> Key {
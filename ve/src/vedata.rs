@@ -1,19 +1,30 @@
 use crate::cep47::NFTToken;
-use crate::data;
+use crate::data::{self, OwnedTokens};
 use crate::dict::Dict;
 use crate::error::VeError;
 use crate::lock::{self, *};
-use crate::utils::{self, require};
+use crate::roles::{self, METADATA_ADMIN_ROLE, MIGRATOR_ROLE, PAUSER_ROLE, TEAM_ROLE, VOTER_ROLE};
+use crate::event::VeEvent;
+use crate::native;
+use crate::smart_wallet;
+use crate::timelock::{
+    self, QueuedOperation, MAX_TIMELOCK_DELAY, MIN_TIMELOCK_DELAY, OP_PROPOSE_TEAM,
+    OP_PROPOSE_VOTER, OP_SET_ART_PROXY, OP_SET_ASSET_WEIGHT, OP_SET_CHECKPOINT_MAX_WEEKS,
+    OP_GRANT_ROLE, OP_REVOKE_ROLE, OP_SET_STRICT_TRANSFERS, OP_SET_TIMELOCK_DELAY,
+};
+use crate::utils::{self, current_block_timestamp_seconds, require};
 use crate::utils::{get_key, set_key};
+use crate::erc20_helpers::{TokenStandard, TOKEN_STANDARD};
 use crate::{erc20_helpers, CEP47, I128::*, TokenId};
 use alloc::{
+    boxed::Box,
     collections::BTreeMap,
     string::{String, ToString},
     vec::Vec,
 };
 use casper_contract::{
     contract_api::{
-        runtime::{self, get_blocktime},
+        runtime,
         runtime::print,
         storage,
     },
@@ -32,6 +43,7 @@ pub const ARG_TOKEN_ID: &str = "token_id";
 
 pub const POINT_HISTORY: &str = "point_history";
 pub const IS_LOCKED: &str = "is_locked";
+pub const PAUSED_FLAGS: &str = "paused_flags";
 pub const TOKEN_CONTRACT_HASH: &str = "token_contract_hash";
 pub const ART_PROXY_CONTRACT_HASH: &str = "art_proxy_contract_hash";
 pub const VOTER: &str = "voter";
@@ -45,9 +57,21 @@ pub const SLOPE_CHANGES: &str = "slope_changes";
 pub const SUPPLY: &str = "supply";
 pub const EPOCH_INDEX: &str = "epoch_index";
 pub const VE_SUPPLY: &str = "ve_supply";
+pub const TOTAL_LOCKED: &str = "total_locked";
+/// Last token id handed out, ids of burned NFTs are never reused
+pub const TOKEN_ID: &str = "token_id";
+pub const STRICT_TRANSFERS: &str = "strict_transfers";
+pub const ASSET_WEIGHTS: &str = "asset_weights";
+pub const ASSET_SUPPLY: &str = "asset_supply";
+pub const ESCROW_TYPE: &str = "escrow_type";
+pub const MANAGED_OF: &str = "managed_of";
+pub const MANAGED_SHARES: &str = "managed_shares";
+pub const ARG_ASSET: &str = "asset";
+pub const ARG_WEIGHT: &str = "weight";
 pub const ARG_AMOUNT: &str = "amount";
 pub const ARG_LOCK_DURATION: &str = "lock_duration";
 pub const DELEGATES: &str = "delegates";
+pub const TOKEN_DELEGATES: &str = "token_delegates";
 pub const CHECKPOINTS: &str = "checkpoints";
 pub const NUM_CHECKPOINTS: &str = "num_checkpoints";
 pub const NONCES: &str = "nonces";
@@ -60,35 +84,91 @@ pub const ARG_FROM: &str = "from";
 pub const ARG_TO: &str = "to";
 pub const DELEGATOR: &str = "delegator";
 pub const ARG_TIMESTAMP: &str = "timestamp";
+pub const ARG_OWNER: &str = "owner";
+pub const ARG_OFFSET: &str = "offset";
+pub const ARG_LIMIT: &str = "limit";
+pub const ARG_TOKEN_IDS: &str = "token_ids";
+pub const ARG_ACCOUNTS: &str = "accounts";
+pub const ARG_FROM_TS: &str = "from_ts";
+pub const ARG_TO_TS: &str = "to_ts";
+pub const ARG_STEP: &str = "step";
+pub const ARG_MAX_WEEKS: &str = "max_weeks";
+pub const CHECKPOINT_MAX_WEEKS: &str = "checkpoint_max_weeks";
+pub const SCHEMA_VERSION: &str = "schema_version";
+pub const ARG_ROLE: &str = "role";
+pub const ARG_ACCOUNT: &str = "account";
+pub const ARG_NEW_TEAM: &str = "new_team";
+pub const ARG_NEW_VOTER: &str = "new_voter";
+pub const ARG_FLAGS: &str = "flags";
+pub const ARG_ENABLED: &str = "enabled";
+pub const ARG_CHECKER: &str = "checker";
+pub const ARG_OP_ID: &str = "op_id";
+pub const ARG_DELAY: &str = "delay";
+pub const ARG_TOKEN: &str = "token";
+pub const ARG_RECIPIENT: &str = "recipient";
+pub const ARG_MANAGED_ID: &str = "managed_id";
+pub const ARG_DELEGATEE: &str = "delegatee";
 
 pub const DEPOSIT_FOR_TYPE: u8 = 0;
 pub const CREATE_LOCK_TYPE: u8 = 1;
 pub const INCREASE_LOCK_AMOUNT: u8 = 2;
 pub const INCREASE_UNLOCK_TIME: u8 = 3;
 pub const MERGE_TYPE: u8 = 4;
+/// A normal lock, a lock deposited into a managed NFT, and a managed NFT itself
+pub const ESCROW_TYPE_NORMAL: u8 = 0;
+pub const ESCROW_TYPE_LOCKED: u8 = 1;
+pub const ESCROW_TYPE_MANAGED: u8 = 2;
+/// Every managed NFT is priced as if it held this many extra shares and one extra unit,
+/// so inflating its share price by donations costs far more than it can take
+pub const MANAGED_VIRTUAL_SHARES: u128 = 1_000_000;
+pub const MANAGED_VIRTUAL_AMOUNT: u128 = 1;
 pub const WEEK: u128 = 86400 * 7;
 pub const MAXTIME: u128 = 26 * 86400 * 7;
 pub const I_MAXTIME: i128 = 26 * 86400 * 7;
 pub const MULTIPLIER: u128 = 1_000_000_000_000_000_000;
 pub const MAX_DELEGATES: u64 = 1024;
-
-pub fn current_block_timestamp_seconds() -> u64 {
-    u64::from(get_blocktime()).checked_rem(u64::MAX).unwrap() / 1000
-}
+pub const MAX_PAGE_SIZE: u64 = 50;
+pub const MAX_BATCH_SIZE: usize = 100;
+pub const MAX_CURVE_SAMPLES: u64 = 200;
+pub const DEFAULT_CHECKPOINT_MAX_WEEKS: u64 = 52;
+/// Storage layout version written by `initialize` and reached by `migrate`
+pub const CURRENT_SCHEMA_VERSION: u32 = 13;
+/// Asset weights are expressed in basis points of voting power per locked unit
+pub const WEIGHT_PRECISION: u64 = 10_000;
+pub const MAX_ASSET_WEIGHT: u64 = 10 * WEIGHT_PRECISION;
 
 pub fn current_block_number() -> u64 {
     100
 }
 
+/// `asset` is the null key for the primary underlying, `weight` is the asset weight
+/// snapshot taken when the lock was created. Permanent locks have no `end` and don't decay.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct LockedBalance {
     pub amount: u128,
     pub end: u64,
+    pub asset: Key,
+    pub weight: u64,
+    pub is_permanent: bool,
+}
+
+impl LockedBalance {
+    /// @notice Amount counted for voting power, `amount * weight`
+    pub fn weighted_amount(&self) -> u128 {
+        (U256::from(self.amount) * U256::from(self.weight) / U256::from(WEIGHT_PRECISION))
+            .as_u128()
+    }
 }
 
 impl Default for LockedBalance {
     fn default() -> Self {
-        LockedBalance { amount: 0, end: 0 }
+        LockedBalance {
+            amount: 0,
+            end: 0,
+            asset: utils::null_key(),
+            weight: WEIGHT_PRECISION,
+            is_permanent: false,
+        }
     }
 }
 
@@ -97,11 +177,18 @@ impl ToBytes for LockedBalance {
         let mut result = bytesrepr::allocate_buffer(self)?;
         result.extend(U128::from(self.amount).to_bytes()?);
         result.extend(self.end.to_bytes()?);
+        result.extend(self.asset.to_bytes()?);
+        result.extend(self.weight.to_bytes()?);
+        result.extend(self.is_permanent.to_bytes()?);
         Ok(result)
     }
 
     fn serialized_length(&self) -> usize {
-        U128::from(self.amount).serialized_length() + self.end.serialized_length()
+        U128::from(self.amount).serialized_length()
+            + self.end.serialized_length()
+            + self.asset.serialized_length()
+            + self.weight.serialized_length()
+            + self.is_permanent.serialized_length()
     }
 }
 
@@ -110,7 +197,33 @@ impl FromBytes for LockedBalance {
         let (amount, remainder) = U128::from_bytes(bytes)?;
         let amount = amount.as_u128();
         let (end, remainder) = u64::from_bytes(remainder)?;
-        Ok((LockedBalance { amount, end }, remainder))
+        // Locks stored before multi-asset support end here, they hold the primary asset
+        if remainder.is_empty() {
+            let locked = LockedBalance {
+                amount,
+                end,
+                ..LockedBalance::default()
+            };
+            return Ok((locked, remainder));
+        }
+        let (asset, remainder) = Key::from_bytes(remainder)?;
+        let (weight, remainder) = u64::from_bytes(remainder)?;
+        // Locks stored before managed NFTs are never permanent
+        let (is_permanent, remainder) = if remainder.is_empty() {
+            (false, remainder)
+        } else {
+            bool::from_bytes(remainder)?
+        };
+        Ok((
+            LockedBalance {
+                amount,
+                end,
+                asset,
+                weight,
+                is_permanent,
+            },
+            remainder,
+        ))
     }
 }
 
@@ -229,14 +342,78 @@ impl CLTyped for LockedBalance {
     }
 }
 
-pub fn initialize(token_contract: Key, art_proxy_contract: Key) {
+#[derive(Clone)]
+pub struct SolvencyReport {
+    pub ve_supply: u128,
+    pub balance: u128,
+    pub total_locked: u128,
+    pub solvent: bool,
+}
+
+impl ToBytes for SolvencyReport {
+    fn to_bytes(&self) -> Result<Vec<u8>, casper_types::bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend(U128::from(self.ve_supply).to_bytes()?);
+        result.extend(U128::from(self.balance).to_bytes()?);
+        result.extend(U128::from(self.total_locked).to_bytes()?);
+        result.extend(self.solvent.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U128::from(self.ve_supply).serialized_length()
+            + U128::from(self.balance).serialized_length()
+            + U128::from(self.total_locked).serialized_length()
+            + self.solvent.serialized_length()
+    }
+}
+
+impl FromBytes for SolvencyReport {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), casper_types::bytesrepr::Error> {
+        let (ve_supply, remainder) = U128::from_bytes(bytes)?;
+        let (balance, remainder) = U128::from_bytes(remainder)?;
+        let (total_locked, remainder) = U128::from_bytes(remainder)?;
+        let (solvent, remainder) = bool::from_bytes(remainder)?;
+        Ok((
+            SolvencyReport {
+                ve_supply: ve_supply.as_u128(),
+                balance: balance.as_u128(),
+                total_locked: total_locked.as_u128(),
+                solvent,
+            },
+            remainder,
+        ))
+    }
+}
+
+impl CLTyped for SolvencyReport {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+pub fn initialize(
+    token_contract: Key,
+    art_proxy_contract: Key,
+    token_standard: u8,
+    native_lock: bool,
+) {
     runtime::print("initialize");
     lock::init();
     let caller = utils::get_immediate_caller_key();
+    require(
+        TokenStandard::from_u8(token_standard).is_some(),
+        VeError::InvalidTokenStandard,
+    );
     set_key(TOKEN_CONTRACT_HASH, token_contract);
+    set_key(TOKEN_STANDARD, token_standard);
+    native::init(native_lock);
     set_key(ART_PROXY_CONTRACT_HASH, art_proxy_contract);
-    set_key(TEAM, caller);
-    set_key(VOTER, caller);
+    roles::init(caller);
+    smart_wallet::init();
+    timelock::init();
+    set_key(SCHEMA_VERSION, CURRENT_SCHEMA_VERSION);
+    set_key(TOKEN_ID, 0u64);
 
     storage::new_dictionary(POINT_HISTORY).unwrap_or_revert_with(VeError::FailedToCreateDictionary);
 
@@ -254,22 +431,577 @@ pub fn initialize(token_contract: Key, art_proxy_contract: Key) {
     dao_voting_storage_init();
 }
 
+fn only_team() {
+    roles::only_role(TEAM_ROLE, VeError::NOTTEAM);
+}
+
+/// @notice Queue a proposal handing the caller's team role over to `new_team`
+/// @dev Once executed nothing changes until `new_team` calls `accept_team`
+/// @return The id of the queued operation
 #[no_mangle]
-pub extern "C" fn set_team() {
-    let current_team: Key = get_key(TEAM).unwrap();
-    let caller = utils::get_immediate_caller_key();
-    require(caller == current_team, VeError::NOTTEAM);
-    let new_team: Key = runtime::get_named_arg("new_team");
-    set_key(TEAM, new_team);
+pub extern "C" fn propose_team() {
+    let new_team: Key = runtime::get_named_arg(ARG_NEW_TEAM);
+    let op_id = non_reentrant(|| {
+        only_team();
+        timelock::queue(OP_PROPOSE_TEAM, new_team, 0)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn accept_team() {
+    non_reentrant(|| {
+        roles::accept_transfer(TEAM_ROLE);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn cancel_team() {
+    non_reentrant(|| {
+        only_team();
+        roles::cancel_transfer(TEAM_ROLE);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn pending_team() {
+    let pending = roles::pending_transfer(TEAM_ROLE).map(|(to, _)| to);
+    runtime::ret(CLValue::from_t(pending).unwrap_or_revert());
 }
 
+/// @notice Queue replacing the art proxy
+/// @return The id of the queued operation
 #[no_mangle]
 pub extern "C" fn set_art_proxy() {
-    let current_team: Key = get_key(TEAM).unwrap();
-    let caller = utils::get_immediate_caller_key();
-    require(caller == current_team, VeError::NOTTEAM);
     let new_ap: Key = runtime::get_named_arg("new_art_proxy");
-    set_key(ART_PROXY_CONTRACT_HASH, new_ap);
+    let op_id = non_reentrant(|| {
+        roles::only_role(METADATA_ADMIN_ROLE, VeError::MissingRole);
+        timelock::queue(OP_SET_ART_PROXY, new_ap, 0)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             TIMELOCK
+//////////////////////////////////////////////////////////////*/
+fn _apply_operation(op: &QueuedOperation) {
+    match op.kind {
+        OP_SET_ART_PROXY => set_key(ART_PROXY_CONTRACT_HASH, op.target),
+        OP_PROPOSE_TEAM => roles::propose_transfer_as(TEAM_ROLE, op.proposer, op.target),
+        OP_PROPOSE_VOTER => roles::propose_transfer_as(VOTER_ROLE, op.proposer, op.target),
+        OP_SET_CHECKPOINT_MAX_WEEKS => {
+            require(op.value > 0, VeError::InvalidCheckpointBudget);
+            set_key(CHECKPOINT_MAX_WEEKS, op.value);
+        }
+        OP_SET_TIMELOCK_DELAY => timelock::set_delay(op.value),
+        OP_SET_STRICT_TRANSFERS => set_key(STRICT_TRANSFERS, op.value != 0),
+        OP_SET_ASSET_WEIGHT => {
+            require(op.value <= MAX_ASSET_WEIGHT, VeError::InvalidAssetWeight);
+            Dict::instance(ASSET_WEIGHTS).set_by_key(&op.target, op.value);
+        }
+        OP_GRANT_ROLE | OP_REVOKE_ROLE => {
+            let role = roles::at_index(op.value).unwrap_or_revert_with(VeError::UnknownRole);
+            if op.kind == OP_GRANT_ROLE {
+                roles::grant(role, op.target, op.proposer);
+            } else {
+                roles::revoke(role, op.target, op.proposer);
+            }
+        }
+        _ => runtime::revert(VeError::UnknownOperationKind),
+    }
+}
+
+/// @notice Apply a queued operation, anyone can call this once its eta has passed
+#[no_mangle]
+pub extern "C" fn execute_queued() {
+    let op_id: u64 = runtime::get_named_arg(ARG_OP_ID);
+    non_reentrant(|| {
+        let op = timelock::take_ready(op_id);
+        _apply_operation(&op);
+    });
+}
+
+/// @notice Drop a queued operation, callable by the team or the account that queued it
+#[no_mangle]
+pub extern "C" fn cancel_queued() {
+    let op_id: u64 = runtime::get_named_arg(ARG_OP_ID);
+    non_reentrant(|| {
+        let caller = utils::get_immediate_caller_key();
+        let op = timelock::get(op_id).unwrap_or_revert_with(VeError::UnknownOperation);
+        if caller != op.proposer {
+            only_team();
+        }
+        timelock::cancel(op_id);
+    });
+}
+
+/// @notice Queue a new delay for operations queued after it is executed
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn set_timelock_delay() {
+    let delay: u64 = runtime::get_named_arg(ARG_DELAY);
+    let op_id = non_reentrant(|| {
+        only_team();
+        require(
+            (MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&delay),
+            VeError::InvalidTimelockDelay,
+        );
+        timelock::queue(OP_SET_TIMELOCK_DELAY, utils::null_key(), delay)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+/// @notice Queue switching strict mode, where deposits revert unless the full amount arrives
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn set_strict_transfers() {
+    let enabled: bool = runtime::get_named_arg(ARG_ENABLED);
+    let op_id = non_reentrant(|| {
+        only_team();
+        timelock::queue(OP_SET_STRICT_TRANSFERS, utils::null_key(), enabled as u64)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn timelock_delay() {
+    runtime::ret(CLValue::from_t(timelock::delay()).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn queued_operation() {
+    let op_id: u64 = runtime::get_named_arg(ARG_OP_ID);
+    let op = timelock::get(op_id).unwrap_or_revert_with(VeError::UnknownOperation);
+    runtime::ret(CLValue::from_t(op).unwrap_or_revert());
+}
+
+/// @param limit Maximum number of entries, capped at MAX_PAGE_SIZE
+#[no_mangle]
+pub extern "C" fn queued_operations() {
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    let ops = timelock::operations(offset, core::cmp::min(limit, MAX_PAGE_SIZE));
+    runtime::ret(CLValue::from_t(ops).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             PAUSE
+//////////////////////////////////////////////////////////////*/
+fn only_pauser() {
+    let caller = utils::get_immediate_caller_key();
+    require(
+        roles::has_role(TEAM_ROLE, caller) || roles::has_role(PAUSER_ROLE, caller),
+        VeError::MissingRole,
+    );
+}
+
+/// @notice Pause the operation classes set in `flags`, see the PAUSE_* constants in `lock`
+#[no_mangle]
+pub extern "C" fn pause() {
+    let flags: u8 = runtime::get_named_arg(ARG_FLAGS);
+    non_reentrant(|| {
+        only_pauser();
+        lock::set_paused_flags(lock::paused_flags() | flags);
+        data::emit_ve(&VeEvent::Paused {
+            flags,
+            sender: utils::get_immediate_caller_key(),
+        });
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn unpause() {
+    let flags: u8 = runtime::get_named_arg(ARG_FLAGS);
+    non_reentrant(|| {
+        only_pauser();
+        lock::set_paused_flags(lock::paused_flags() & !flags);
+        data::emit_ve(&VeEvent::Unpaused {
+            flags,
+            sender: utils::get_immediate_caller_key(),
+        });
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn paused_flags() {
+    runtime::ret(CLValue::from_t(lock::paused_flags()).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             ASSETS
+//////////////////////////////////////////////////////////////*/
+/// @notice The primary underlying is stored as the null key
+fn _normalize_asset(asset: Key) -> Key {
+    let underlying: Key = get_key(TOKEN_CONTRACT_HASH).unwrap_or_revert();
+    if asset == underlying {
+        utils::null_key()
+    } else {
+        asset
+    }
+}
+
+fn _asset_token(asset: Key) -> Key {
+    if utils::is_null(asset) {
+        get_key(TOKEN_CONTRACT_HASH).unwrap_or_revert()
+    } else {
+        asset
+    }
+}
+
+/// @notice Weight for new locks of `asset`, 0 when it isn't accepted
+fn _asset_weight(asset: Key) -> u64 {
+    let weight: Option<u64> = Dict::instance(ASSET_WEIGHTS).get_by_key(&asset);
+    if utils::is_null(asset) {
+        weight.unwrap_or(WEIGHT_PRECISION)
+    } else {
+        weight.unwrap_or(0)
+    }
+}
+
+/// @notice Queue setting the weight of `asset` in basis points, 0 stops new locks of it
+/// @dev Existing locks keep the weight they were created with
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn set_asset_weight() {
+    let asset: Key = runtime::get_named_arg(ARG_ASSET);
+    let weight: u64 = runtime::get_named_arg(ARG_WEIGHT);
+    let op_id = non_reentrant(|| {
+        only_team();
+        require(weight <= MAX_ASSET_WEIGHT, VeError::InvalidAssetWeight);
+        timelock::queue(OP_SET_ASSET_WEIGHT, _normalize_asset(asset), weight)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn asset_weight() {
+    let asset: Key = runtime::get_named_arg(ARG_ASSET);
+    runtime::ret(CLValue::from_t(_asset_weight(_normalize_asset(asset))).unwrap_or_revert());
+}
+
+/// @notice Amount of `asset` currently locked
+#[no_mangle]
+pub extern "C" fn asset_supply() {
+    let asset = _normalize_asset(runtime::get_named_arg(ARG_ASSET));
+    let supply: U128 = if utils::is_null(asset) {
+        get_key(VE_SUPPLY).unwrap_or_revert()
+    } else {
+        Dict::instance(ASSET_SUPPLY).get_by_key(&asset).unwrap_or_default()
+    };
+    runtime::ret(CLValue::from_t(supply).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             SOLVENCY
+//////////////////////////////////////////////////////////////*/
+fn _add_total_locked(value: u128) {
+    let total: U128 = get_key(TOTAL_LOCKED).unwrap_or_revert();
+    set_key(TOTAL_LOCKED, total + U128::from(value));
+}
+
+fn _sub_total_locked(value: u128) {
+    let total: U128 = get_key(TOTAL_LOCKED).unwrap_or_revert();
+    set_key(TOTAL_LOCKED, total - U128::from(value));
+}
+
+/// @notice Record `value` more of `asset` as locked. VE_SUPPLY only counts the
+///         primary asset, other assets have their own supply
+fn _add_locked_supply(asset: Key, value: u128) {
+    if utils::is_null(asset) {
+        let supply: U128 = get_key(VE_SUPPLY).unwrap_or_revert();
+        set_key(VE_SUPPLY, supply + U128::from(value));
+    } else {
+        let dict = Dict::instance(ASSET_SUPPLY);
+        let supply: U128 = dict.get_by_key(&asset).unwrap_or_default();
+        dict.set_by_key(&asset, supply + U128::from(value));
+    }
+}
+
+fn _sub_locked_supply(asset: Key, value: u128) {
+    if utils::is_null(asset) {
+        let supply: U128 = get_key(VE_SUPPLY).unwrap_or_revert();
+        set_key(VE_SUPPLY, supply - U128::from(value));
+    } else {
+        let dict = Dict::instance(ASSET_SUPPLY);
+        let supply: U128 = dict.get_by_key(&asset).unwrap_or_default();
+        dict.set_by_key(&asset, supply - U128::from(value));
+    }
+}
+
+/// @notice Compare the recorded supply, the running sum of locked amounts and the
+///         escrow's underlying balance
+/// @dev `solvent` holds when supply and locked sum agree and the balance covers them
+#[no_mangle]
+pub extern "C" fn solvency() {
+    let ve_supply: U128 = get_key(VE_SUPPLY).unwrap_or_revert();
+    let total_locked: U128 = get_key(TOTAL_LOCKED).unwrap_or_revert();
+    let balance = _underlying_balance();
+    let report = SolvencyReport {
+        ve_supply: ve_supply.as_u128(),
+        balance,
+        total_locked: total_locked.as_u128(),
+        solvent: ve_supply == total_locked && balance >= total_locked.as_u128(),
+    };
+    runtime::ret(CLValue::from_t(report).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             RESCUE
+//////////////////////////////////////////////////////////////*/
+/// @notice Amount of `token` held by the escrow that no lock accounts for
+fn _rescuable_amount(token: Key) -> u128 {
+    let balance = erc20_helpers::get_balance(token, utils::get_self_key());
+    let underlying: Key = get_key(TOKEN_CONTRACT_HASH).unwrap_or_revert();
+    let supply: U128 = if !native::is_enabled() && token == underlying {
+        get_key(VE_SUPPLY).unwrap_or_revert()
+    } else {
+        Dict::instance(ASSET_SUPPLY).get_by_key(&token).unwrap_or_default()
+    };
+    balance.saturating_sub(supply.as_u128())
+}
+
+/// @notice Return tokens sent to the escrow by mistake
+/// @dev For lockable assets only the surplus above their locked supply can be moved
+#[no_mangle]
+pub extern "C" fn rescue_token() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let recipient: Key = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let amount = amount.as_u128();
+    non_reentrant(|| {
+        only_team();
+        require(amount > 0, VeError::InvalidAmount);
+        require(
+            amount <= _rescuable_amount(token),
+            VeError::RescueExceedsSurplus,
+        );
+        erc20_helpers::transfer(token, recipient, amount);
+        data::emit_ve(&VeEvent::TokenRescued {
+            token,
+            recipient,
+            amount,
+            sender: utils::get_immediate_caller_key(),
+        });
+    });
+}
+
+////////////////////////////////////////////////////////////////
+//                             SMART WALLET CHECKER
+//////////////////////////////////////////////////////////////*/
+/// @notice Turn the contract caller allowlist for new locks on or off
+#[no_mangle]
+pub extern "C" fn set_smart_wallet_check() {
+    let enabled: bool = runtime::get_named_arg(ARG_ENABLED);
+    non_reentrant(|| {
+        only_team();
+        smart_wallet::set_enabled(enabled);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn allow_smart_wallet() {
+    let address: Key = runtime::get_named_arg(ARG_ADDRESS);
+    non_reentrant(|| {
+        only_team();
+        smart_wallet::set_allowed(address, true);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn disallow_smart_wallet() {
+    let address: Key = runtime::get_named_arg(ARG_ADDRESS);
+    non_reentrant(|| {
+        only_team();
+        smart_wallet::set_allowed(address, false);
+    });
+}
+
+/// @notice Set the contract asked through its `check(address) -> bool` entry point
+///         about callers missing from the allowlist, `None` to only use the allowlist
+#[no_mangle]
+pub extern "C" fn set_smart_wallet_checker() {
+    let checker: Option<Key> = runtime::get_named_arg(ARG_CHECKER);
+    non_reentrant(|| {
+        only_team();
+        smart_wallet::set_checker(checker);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn is_smart_wallet_allowed() {
+    let address: Key = runtime::get_named_arg(ARG_ADDRESS);
+    runtime::ret(CLValue::from_t(smart_wallet::is_allowed(address)).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             ROLES
+//////////////////////////////////////////////////////////////*/
+/// @notice Give `account` the `role`, only the team administers roles
+/// @dev Privileged roles are only granted through `queue_grant_role`
+#[no_mangle]
+pub extern "C" fn grant_role() {
+    let role: String = runtime::get_named_arg(ARG_ROLE);
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    non_reentrant(|| {
+        only_team();
+        require(roles::is_known(&role), VeError::UnknownRole);
+        require(!roles::is_privileged(&role), VeError::PrivilegedRole);
+        roles::grant(&role, account, utils::get_immediate_caller_key());
+    });
+}
+
+/// @dev Privileged roles are only revoked through `queue_revoke_role`
+#[no_mangle]
+pub extern "C" fn revoke_role() {
+    let role: String = runtime::get_named_arg(ARG_ROLE);
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    non_reentrant(|| {
+        only_team();
+        require(roles::is_known(&role), VeError::UnknownRole);
+        require(!roles::is_privileged(&role), VeError::PrivilegedRole);
+        roles::revoke(&role, account, utils::get_immediate_caller_key());
+    });
+}
+
+fn _queue_role_change(kind: u8) -> u64 {
+    let role: String = runtime::get_named_arg(ARG_ROLE);
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    non_reentrant(|| {
+        only_team();
+        let index = roles::index_of(&role).unwrap_or_revert_with(VeError::UnknownRole);
+        timelock::queue(kind, account, index)
+    })
+}
+
+/// @notice Queue giving `account` the `role`, the way privileged roles are granted
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn queue_grant_role() {
+    let op_id = _queue_role_change(OP_GRANT_ROLE);
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+/// @notice Queue taking the `role` from `account`
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn queue_revoke_role() {
+    let op_id = _queue_role_change(OP_REVOKE_ROLE);
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
+}
+
+/// @notice Give up one of the caller's own roles
+#[no_mangle]
+pub extern "C" fn renounce_role() {
+    let role: String = runtime::get_named_arg(ARG_ROLE);
+    non_reentrant(|| {
+        let caller = utils::get_immediate_caller_key();
+        require(roles::has_role(&role, caller), VeError::MissingRole);
+        roles::revoke(&role, caller, caller);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn has_role() {
+    let role: String = runtime::get_named_arg(ARG_ROLE);
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    runtime::ret(CLValue::from_t(roles::has_role(&role, account)).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             MIGRATION
+//////////////////////////////////////////////////////////////*/
+/// @notice Bring storage written by an older contract version to `version`
+/// @dev Each step must leave storage as `initialize` of that version would
+fn _migrate_to(version: u32) {
+    match version {
+        1 => {
+            // Keys introduced after the first deployed release
+            if runtime::get_key(CHECKPOINT_MAX_WEEKS).is_none() {
+                set_key(CHECKPOINT_MAX_WEEKS, DEFAULT_CHECKPOINT_MAX_WEEKS);
+            }
+        }
+        2 => {
+            // The single team and voter keys become role members
+            let team: Key = get_key(TEAM).unwrap_or_revert();
+            let voter: Key = get_key(VOTER).unwrap_or_revert();
+            roles::init(team);
+            roles::revoke(VOTER_ROLE, team, team);
+            roles::grant(VOTER_ROLE, voter, team);
+            roles::set_holder(VOTER_ROLE, voter);
+            runtime::remove_key(TEAM);
+            runtime::remove_key(VOTER);
+        }
+        3 => smart_wallet::init(),
+        4 => timelock::init(),
+        5 => {
+            // Locks created before this version were never summed, start from the supply
+            let supply: U128 = get_key(VE_SUPPLY).unwrap_or_revert();
+            set_key(TOTAL_LOCKED, supply);
+        }
+        6 => set_key(STRICT_TRANSFERS, false),
+        7 => set_key(TOKEN_STANDARD, TokenStandard::Erc20 as u8),
+        8 => native::init(false),
+        9 => {
+            Dict::init(ASSET_WEIGHTS);
+            Dict::init(ASSET_SUPPLY);
+        }
+        10 => {
+            Dict::init(ESCROW_TYPE);
+            Dict::init(MANAGED_OF);
+            Dict::init(MANAGED_SHARES);
+        }
+        11 => Dict::init(TOKEN_DELEGATES),
+        12 => {
+            // Ids were derived from the NFT supply, which withdrawals burn down. Every id
+            // handed out so far has a checkpoint, continue after the last of them.
+            let mut last_id = data::total_supply().as_u64();
+            let epochs = Dict::instance(USER_POINT_EPOCH);
+            while epochs.get::<u64>(&(last_id + 1).to_string()).unwrap_or(0) > 0 {
+                last_id += 1;
+            }
+            set_key(TOKEN_ID, last_id);
+        }
+        // Pause flags were added without a step of their own, only a pause created the key
+        13 => {
+            if runtime::get_key(PAUSED_FLAGS).is_none() {
+                lock::set_paused_flags(0);
+            }
+        }
+        _ => runtime::revert(VeError::InvalidMigrationFlag),
+    }
+}
+
+/// @notice Run every migration step between the stored and the current schema version
+/// @dev Called by the upgrade session right after the new contract version is added,
+///      a no-op when the schema is already current so code-only upgrades go through
+#[no_mangle]
+pub extern "C" fn migrate() {
+    non_reentrant(|| {
+        let stored: u32 = get_key(SCHEMA_VERSION).unwrap_or(0);
+        if stored < 2 {
+            // Roles don't exist before schema version 2
+            let current_team: Key = get_key(TEAM).unwrap();
+            let caller = utils::get_immediate_caller_key();
+            require(caller == current_team, VeError::NOTTEAM);
+        } else {
+            roles::only_role(MIGRATOR_ROLE, VeError::MissingRole);
+        }
+
+        if stored == CURRENT_SCHEMA_VERSION {
+            return;
+        }
+        require(stored < CURRENT_SCHEMA_VERSION, VeError::ContractAlreadyMigrated);
+        for version in stored + 1..=CURRENT_SCHEMA_VERSION {
+            _migrate_to(version);
+        }
+        set_key(SCHEMA_VERSION, CURRENT_SCHEMA_VERSION);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn schema_version() {
+    let version: u32 = get_key(SCHEMA_VERSION).unwrap_or(0);
+    runtime::ret(CLValue::from_t(version).unwrap_or_revert());
 }
 
 ////////////////////////////////////////////////////////////////
@@ -288,6 +1020,14 @@ fn escrow_init() {
 
     set_key(EPOCH, 0u64);
     set_key(VE_SUPPLY, U128::from(0));
+    set_key(TOTAL_LOCKED, U128::from(0));
+    set_key(STRICT_TRANSFERS, false);
+    Dict::init(ASSET_WEIGHTS);
+    Dict::init(ASSET_SUPPLY);
+    Dict::init(ESCROW_TYPE);
+    Dict::init(MANAGED_OF);
+    Dict::init(MANAGED_SHARES);
+    set_key(CHECKPOINT_MAX_WEEKS, DEFAULT_CHECKPOINT_MAX_WEEKS);
 }
 
 pub fn get_locked_balance(token_id: u64) -> LockedBalance {
@@ -300,6 +1040,19 @@ pub fn get_locked_balance(token_id: u64) -> LockedBalance {
     LockedBalance::default()
 }
 
+/// @notice Store the lock of `token_id`, every write goes through here so TOTAL_LOCKED
+///         is the sum of the stored primary asset amounts
+fn set_locked_balance(token_id: u64, locked: &LockedBalance) {
+    let old_locked = get_locked_balance(token_id);
+    if utils::is_null(old_locked.asset) {
+        _sub_total_locked(old_locked.amount);
+    }
+    if utils::is_null(locked.asset) {
+        _add_total_locked(locked.amount);
+    }
+    Dict::instance(LOCKED).set(&token_id.to_string(), locked.clone());
+}
+
 pub fn get_slope_changes(time: u64) -> i128 {
     let dict = Dict::instance(SLOPE_CHANGES);
     let sc: I128 = dict.get(&time.to_string()).unwrap_or(I128::from(0));
@@ -346,6 +1099,33 @@ pub extern "C" fn user_point_history__ts() {
     runtime::ret(CLValue::from_t(U128::from(point.ts)).unwrap_or_revert());
 }
 
+#[no_mangle]
+pub extern "C" fn user_point_epoch() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let dict = Dict::instance(USER_POINT_EPOCH);
+    let uepoch: u64 = dict.get(&token_id.to_string()).unwrap_or(0);
+    runtime::ret(CLValue::from_t(uepoch).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn user_point_history() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let epoch_index: u64 = runtime::get_named_arg(EPOCH_INDEX);
+    runtime::ret(CLValue::from_t(get_user_point(token_id, epoch_index)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn point_history() {
+    let epoch_index: u64 = runtime::get_named_arg(EPOCH_INDEX);
+    runtime::ret(CLValue::from_t(get_point(epoch_index as u128)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn epoch() {
+    let epoch: u64 = get_key(EPOCH).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(epoch).unwrap_or_revert());
+}
+
 #[no_mangle]
 pub extern "C" fn locked_end() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
@@ -354,42 +1134,34 @@ pub extern "C" fn locked_end() {
     runtime::ret(CLValue::from_t(locked_balance.end).unwrap_or_revert());
 }
 
-/// @notice Record global and per-user data to checkpoint
-/// @param _tokenId NFT token ID. No user checkpoint if 0
-/// @param old_locked Pevious locked amount / end lock time for the user
-/// @param new_locked New locked amount / end lock time for the user
-pub fn _check_point(token_id: u64, old_locked: &LockedBalance, new_locked: &LockedBalance) {
-    let mut u_old = Point::default();
-    let mut u_new = Point::default();
-    let mut old_dslope = 0i128;
-    let mut new_dslope = 0i128;
+/// @notice Permanent locks have no end, their voting power doesn't decay
+#[no_mangle]
+pub extern "C" fn locked_is_permanent() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let locked_balance = get_locked_balance(token_id);
+
+    runtime::ret(CLValue::from_t(locked_balance.is_permanent).unwrap_or_revert());
+}
+
+/// @notice Token locked by `token_id`, the primary underlying included
+#[no_mangle]
+pub extern "C" fn locked_asset() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let locked_balance = get_locked_balance(token_id);
+
+    runtime::ret(CLValue::from_t(_asset_token(locked_balance.asset)).unwrap_or_revert());
+}
+
+/// @notice Extend the global point history towards the current block time
+/// @dev Every weekly point crossed is written; the point at the current block time
+///      is only returned, its caller records it once its own changes are applied
+/// @param max_weeks Maximum number of week boundaries to process
+/// @return Last point, its epoch and whether the current block time was reached
+fn _fill_point_history(max_weeks: u64) -> (Point, u128, bool) {
     let _epoch: u64 = get_key(EPOCH).unwrap();
     let mut _epoch = _epoch as u128;
     let ts = current_block_timestamp_seconds();
     let block_number = current_block_number();
-    if token_id != 0 {
-        if old_locked.end > ts && old_locked.amount > 0 {
-            u_old.slope = (old_locked.amount / I_MAXTIME.unsigned_abs()) as i128;
-            u_old.bias = u_old.slope * ((old_locked.end - ts) as i128);
-        }
-
-        if new_locked.end > ts && new_locked.amount > 0 {
-            u_new.slope = (new_locked.amount / I_MAXTIME.unsigned_abs()) as i128;
-            u_new.bias = u_new.slope * ((new_locked.end - ts) as i128);
-        }
-
-        // Read values of scheduled changes in the slope
-        // old_locked.end can be in the past and in the future
-        // new_locked.end can ONLY by in the FUTURE unless everything expired: than zeros
-        old_dslope = get_slope_changes(old_locked.end);
-        if new_locked.end != 0 {
-            if new_locked.end == old_locked.end {
-                new_dslope = old_dslope;
-            } else {
-                new_dslope = get_slope_changes(new_locked.end);
-            }
-        }
-    }
 
     let mut last_point = Point {
         bias: 0,
@@ -411,46 +1183,109 @@ pub fn _check_point(token_id: u64, old_locked: &LockedBalance, new_locked: &Lock
             / (ts as u128 - last_point.ts as u128);
     }
 
-    {
-        let mut t_i = (last_checkpoint as u128 / WEEK) * WEEK;
-        for _i in 0..255u128 {
-            // Hopefully it won't happen that this won't get used in 27 weeks!
-            // If it does, users will be able to withdraw but vote weight will be broken
-            t_i = t_i + WEEK as u128;
-            let mut d_slope = 0i128;
-            if t_i > ts as u128 {
-                t_i = ts as u128;
-            } else {
-                d_slope = get_slope_changes(t_i as u64);
-            }
-            last_point.bias =
-                last_point.bias - last_point.slope * ((t_i - last_checkpoint as u128) as i128);
-            last_point.slope = last_point.slope + d_slope;
-            if last_point.bias < 0 {
-                // This can happen
-                last_point.bias = 0;
-            }
-            if last_point.slope < 0 {
-                // This cannot happen - just in case
-                last_point.slope = 0;
-            }
-            last_checkpoint = t_i as u64;
-            last_point.ts = t_i as u64;
-            last_point.blk = initial_last_point.blk
-                + ((block_slope as u128 * (t_i - initial_last_point.ts as u128)) / MULTIPLIER)
-                    as u64;
-            _epoch = _epoch + 1;
-            if t_i == ts as u128 {
-                last_point.blk = block_number;
-                break;
+    let mut reached = false;
+    let mut t_i = (last_checkpoint as u128 / WEEK) * WEEK;
+    for _i in 0..max_weeks {
+        // Points are written as we go, so a history left behind after a long idle
+        // period is caught up over several calls
+        t_i = t_i + WEEK as u128;
+        let mut d_slope = 0i128;
+        if t_i > ts as u128 {
+            t_i = ts as u128;
+        } else {
+            d_slope = get_slope_changes(t_i as u64);
+        }
+        last_point.bias =
+            last_point.bias - last_point.slope * ((t_i - last_checkpoint as u128) as i128);
+        last_point.slope = last_point.slope + d_slope;
+        if last_point.bias < 0 {
+            // This can happen
+            last_point.bias = 0;
+        }
+        if last_point.slope < 0 {
+            // This cannot happen - just in case
+            last_point.slope = 0;
+        }
+        last_checkpoint = t_i as u64;
+        last_point.ts = t_i as u64;
+        last_point.blk = initial_last_point.blk
+            + ((block_slope as u128 * (t_i - initial_last_point.ts as u128)) / MULTIPLIER)
+                as u64;
+        _epoch = _epoch + 1;
+        if t_i == ts as u128 {
+            last_point.blk = block_number;
+            reached = true;
+            break;
+        } else {
+            // set point history
+            dict.set(&_epoch.to_string(), last_point.clone());
+            // point_history[_epoch] = last_point;
+        }
+    }
+    (last_point, _epoch, reached)
+}
+
+/// @notice Number of `_fill_point_history` iterations needed to reach the current block time
+fn _pending_checkpoint_weeks() -> u64 {
+    let _epoch: u64 = get_key(EPOCH).unwrap();
+    if _epoch == 0 {
+        return 1;
+    }
+    let ts = current_block_timestamp_seconds();
+    let last_week = get_point(_epoch as u128).ts / WEEK as u64 * WEEK as u64;
+    if ts <= last_week {
+        return 1;
+    }
+    (ts - last_week + WEEK as u64 - 1) / WEEK as u64
+}
+
+/// @notice Record global and per-user data to checkpoint
+/// @dev Reverts if the global history is further behind than the checkpoint budget,
+///      `check_point_partial` has to be used to catch it up first
+/// @param _tokenId NFT token ID. No user checkpoint if 0
+/// @param old_locked Pevious locked amount / end lock time for the user
+/// @param new_locked New locked amount / end lock time for the user
+pub fn _check_point(token_id: u64, old_locked: &LockedBalance, new_locked: &LockedBalance) {
+    let mut u_old = Point::default();
+    let mut u_new = Point::default();
+    let mut old_dslope = 0i128;
+    let mut new_dslope = 0i128;
+    let ts = current_block_timestamp_seconds();
+    let block_number = current_block_number();
+    if token_id != 0 {
+        // Permanent locks keep their full weight, without slope or scheduled changes
+        if old_locked.is_permanent {
+            u_old.bias = old_locked.weighted_amount() as i128;
+        } else if old_locked.end > ts && old_locked.amount > 0 {
+            u_old.slope = (old_locked.weighted_amount() / I_MAXTIME.unsigned_abs()) as i128;
+            u_old.bias = u_old.slope * ((old_locked.end - ts) as i128);
+        }
+
+        if new_locked.is_permanent {
+            u_new.bias = new_locked.weighted_amount() as i128;
+        } else if new_locked.end > ts && new_locked.amount > 0 {
+            u_new.slope = (new_locked.weighted_amount() / I_MAXTIME.unsigned_abs()) as i128;
+            u_new.bias = u_new.slope * ((new_locked.end - ts) as i128);
+        }
+
+        // Read values of scheduled changes in the slope
+        // old_locked.end can be in the past and in the future
+        // new_locked.end can ONLY by in the FUTURE unless everything expired: than zeros
+        old_dslope = get_slope_changes(old_locked.end);
+        if new_locked.end != 0 {
+            if new_locked.end == old_locked.end {
+                new_dslope = old_dslope;
             } else {
-                // set point history
-                dict.set(&_epoch.to_string(), last_point.clone());
-                // point_history[_epoch] = last_point;
+                new_dslope = get_slope_changes(new_locked.end);
             }
         }
     }
 
+    let max_weeks: u64 = get_key(CHECKPOINT_MAX_WEEKS).unwrap_or(DEFAULT_CHECKPOINT_MAX_WEEKS);
+    let (mut last_point, _epoch, reached) = _fill_point_history(max_weeks);
+    require(reached, VeError::CheckpointRequired);
+    let dict = Dict::instance(POINT_HISTORY);
+
     // update epoch
     set_key(EPOCH, _epoch as u64);
 
@@ -497,6 +1332,47 @@ pub fn _check_point(token_id: u64, old_locked: &LockedBalance, new_locked: &Lock
     }
 }
 
+/// @notice Underlying held by the escrow, the token balance or the purse balance in native mode
+fn _underlying_balance() -> u128 {
+    if native::is_enabled() {
+        return native::balance();
+    }
+    let token: Key = get_key(TOKEN_CONTRACT_HASH).unwrap_or_revert();
+    erc20_helpers::get_balance(token, utils::get_self_key())
+}
+
+fn _asset_balance(asset: Key) -> u128 {
+    if utils::is_null(asset) {
+        _underlying_balance()
+    } else {
+        erc20_helpers::get_balance(asset, utils::get_self_key())
+    }
+}
+
+/// @notice Pull `amount` of `asset` from the caller
+/// @dev Fee-on-transfer tokens deliver less than `amount`, in strict mode that reverts.
+///      In native mode the primary asset is CSPR from the `purse` argument of the call.
+/// @return The amount the escrow received, never more than `amount`
+fn _pull_asset(asset: Key, amount: u128) -> u128 {
+    let balance_before = _asset_balance(asset);
+    if utils::is_null(asset) && native::is_enabled() {
+        native::transfer_in(amount);
+    } else {
+        let token = _asset_token(asset);
+        let escrow = utils::get_self_key();
+        erc20_helpers::transfer_from(token, utils::get_immediate_caller_key(), escrow, amount);
+    }
+    let received = _asset_balance(asset).saturating_sub(balance_before);
+
+    let strict: bool = get_key(STRICT_TRANSFERS).unwrap_or(false);
+    if strict {
+        require(received == amount, VeError::TransferAmountMismatch);
+    } else {
+        require(received > 0, VeError::TransferAmountMismatch);
+    }
+    core::cmp::min(received, amount)
+}
+
 /// @notice Deposit and lock tokens for a user
 /// @param _tokenId NFT that holds lock
 /// @param _value Amount to deposit
@@ -511,20 +1387,25 @@ fn _deposit_for(
     deposit_type: u8,
 ) {
     let mut __locked = locked_balance.clone();
-    let supply_before: U128 = get_key(VE_SUPPLY).unwrap();
-    let supply_before = supply_before.as_u128();
 
-    set_key(VE_SUPPLY, U128::from(supply_before + value));
-    let mut old_locked = LockedBalance::default();
-    old_locked.amount = __locked.amount;
-    old_locked.end = __locked.end;
+    // Tokens are pulled first so only what actually arrived gets credited
+    let value = if value != 0 && deposit_type != MERGE_TYPE {
+        _pull_asset(__locked.asset, value)
+    } else {
+        value
+    };
+
+    // Merged value is already part of the supply, it only moves between locks
+    if deposit_type != MERGE_TYPE {
+        _add_locked_supply(__locked.asset, value);
+    }
+    let old_locked = __locked.clone();
     // Adding to existing lock, or if a lock is expired - creating a new one
     __locked.amount = __locked.amount + value;
     if unlock_time != 0 {
         __locked.end = unlock_time;
     }
-    let dict_locked = Dict::instance(LOCKED);
-    dict_locked.set(&token_id.to_string(), __locked.clone());
+    set_locked_balance(token_id, &__locked);
 
     // Possibilities:
     // Both old_locked.end could be current or expired (>/< block.timestamp)
@@ -532,11 +1413,6 @@ fn _deposit_for(
     // _locked.end > block.timestamp (always)
     _check_point(token_id, &old_locked, &__locked);
 
-    let from = utils::get_immediate_caller_key();
-    let token: Key = get_key(TOKEN_CONTRACT_HASH).unwrap();
-    if value != 0 && deposit_type != MERGE_TYPE {
-        erc20_helpers::transfer_from(token, from, utils::get_self_key(), value);
-    }
     // TODO
     // emit Deposit(from, _tokenId, _value, __locked.end, deposit_type, block.timestamp);
     // emit Supply(supply_before, supply_before + _value);
@@ -544,7 +1420,47 @@ fn _deposit_for(
 
 #[no_mangle]
 pub extern "C" fn check_point() {
-    _check_point(0, &LockedBalance::default(), &LockedBalance::default());
+    non_reentrant(|| {
+        _check_point(0, &LockedBalance::default(), &LockedBalance::default());
+    });
+}
+
+/// @notice Advance the global point history by at most `max_weeks` weeks
+/// @dev Lets keepers catch up a long idle period over several deploys
+#[no_mangle]
+pub extern "C" fn check_point_partial() {
+    let max_weeks: u64 = runtime::get_named_arg(ARG_MAX_WEEKS);
+    require(max_weeks > 0, VeError::InvalidCheckpointBudget);
+
+    non_reentrant(|| {
+        let (last_point, _epoch, reached) = _fill_point_history(max_weeks);
+        set_key(EPOCH, _epoch as u64);
+        if reached {
+            let dict = Dict::instance(POINT_HISTORY);
+            dict.set(&_epoch.to_string(), last_point);
+        }
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn check_point_pending_weeks() {
+    runtime::ret(CLValue::from_t(_pending_checkpoint_weeks()).unwrap_or_revert());
+}
+
+/// @notice Queue a new per-call week budget for checkpoints
+/// @dev Once the point history is more than this many weeks behind, every call that
+///      checkpoints reverts with `CheckpointRequired`, withdraw included. A keeper has to
+///      call `check_point_partial` during idle periods to keep the escrow usable.
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn set_checkpoint_max_weeks() {
+    let max_weeks: u64 = runtime::get_named_arg(ARG_MAX_WEEKS);
+    let op_id = non_reentrant(|| {
+        only_team();
+        require(max_weeks > 0, VeError::InvalidCheckpointBudget);
+        timelock::queue(OP_SET_CHECKPOINT_MAX_WEEKS, utils::null_key(), max_weeks)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
 }
 
 #[no_mangle]
@@ -552,30 +1468,25 @@ pub extern "C" fn deposit_for() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
     let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
     let amount = amount.as_u128();
-    let locked_balance = get_locked_balance(token_id);
+    when_not_paused(PAUSE_DEPOSITS, VeError::DepositsPaused);
+    smart_wallet::assert_allowed_caller();
+    non_reentrant(|| {
+        let locked_balance = get_locked_balance(token_id);
 
-    require(amount > 0, VeError::InvalidAmount);
-    require(locked_balance.amount > 0, VeError::NoExistingLock);
-    require(
-        locked_balance.end > current_block_timestamp_seconds(),
-        VeError::CannotAddToExpiredLock,
-    );
+        require(amount > 0, VeError::InvalidAmount);
+        require(locked_balance.amount > 0 || locked_balance.is_permanent, VeError::NoExistingLock);
+        require(
+            locked_balance.end > current_block_timestamp_seconds() || locked_balance.is_permanent,
+            VeError::CannotAddToExpiredLock,
+        );
 
-    _deposit_for(token_id, amount, 0, &locked_balance, DEPOSIT_FOR_TYPE);
+        _deposit_for(token_id, amount, 0, &locked_balance, DEPOSIT_FOR_TYPE);
+    });
 }
 
-pub fn _create_lock(value: u128, lock_duration: u64, to: Key) -> u64 {
-    let ts = current_block_timestamp_seconds();
-    let unlock_time = (ts + lock_duration) / (WEEK as u64) * (WEEK as u64); // Locktime is rounded down to weeks
-    require(value > 0, VeError::InvalidAmount);
-    require(unlock_time > ts, VeError::CanOnlyLockTillTimeInFuture);
-    require(
-        unlock_time <= ts + MAXTIME as u64,
-        VeError::VotingLockMax26Weeks,
-    );
-
-    let minted_tokens_count = data::total_supply().as_u64();
-    let token_id = minted_tokens_count + 1;
+fn _mint_nft(to: Key) -> u64 {
+    let token_id: u64 = get_key::<u64>(TOKEN_ID).unwrap_or_revert() + 1;
+    set_key(TOKEN_ID, token_id);
     runtime::print("minting token");
     runtime::print(&token_id.to_string());
 
@@ -587,14 +1498,30 @@ pub fn _create_lock(value: u128, lock_duration: u64, to: Key) -> u64 {
         ).unwrap_or_revert();
 
     _move_token_delegates(utils::null_key(), _delegates(to), token_id);
+    token_id
+}
 
-    _deposit_for(
-        token_id,
-        value,
-        unlock_time,
-        &get_locked_balance(token_id),
-        CREATE_LOCK_TYPE,
+pub fn _create_lock(value: u128, lock_duration: u64, to: Key, asset: Key) -> u64 {
+    let asset = _normalize_asset(asset);
+    let weight = _asset_weight(asset);
+    require(weight > 0, VeError::AssetNotAccepted);
+    let ts = current_block_timestamp_seconds();
+    let unlock_time = (ts + lock_duration) / (WEEK as u64) * (WEEK as u64); // Locktime is rounded down to weeks
+    require(value > 0, VeError::InvalidAmount);
+    require(unlock_time > ts, VeError::CanOnlyLockTillTimeInFuture);
+    require(
+        unlock_time <= ts + MAXTIME as u64,
+        VeError::VotingLockMax26Weeks,
     );
+
+    let token_id = _mint_nft(to);
+
+    let new_lock = LockedBalance {
+        asset,
+        weight,
+        ..LockedBalance::default()
+    };
+    _deposit_for(token_id, value, unlock_time, &new_lock, CREATE_LOCK_TYPE);
     token_id
 }
 
@@ -603,14 +1530,16 @@ pub extern "C" fn create_lock() {
     runtime::print("here");
     let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
     let lock_duration: u64 = runtime::get_named_arg(ARG_LOCK_DURATION);
-    when_not_locked();
-    lock_contract();
-    _create_lock(
-        amount.as_u128(),
-        lock_duration,
-        utils::get_immediate_caller_key(),
-    );
-    unlock_contract();
+    when_not_paused(PAUSE_LOCKS, VeError::LocksPaused);
+    smart_wallet::assert_allowed_caller();
+    non_reentrant(|| {
+        _create_lock(
+            amount.as_u128(),
+            lock_duration,
+            utils::get_immediate_caller_key(),
+            utils::null_key(),
+        );
+    });
 }
 
 #[no_mangle]
@@ -619,10 +1548,31 @@ pub extern "C" fn create_lock_for() {
     let lock_duration: u64 = runtime::get_named_arg(ARG_LOCK_DURATION);
     let to: Key = runtime::get_named_arg(ARG_TO);
 
-    when_not_locked();
-    lock_contract();
-    _create_lock(amount.as_u128(), lock_duration, to);
-    unlock_contract();
+    when_not_paused(PAUSE_LOCKS, VeError::LocksPaused);
+    smart_wallet::assert_allowed_caller();
+    non_reentrant(|| {
+        _create_lock(amount.as_u128(), lock_duration, to, utils::null_key());
+    });
+}
+
+/// @notice Deposit `amount` of a team-configured `asset` for the caller
+/// @dev Voting power is `amount` scaled by the asset weight at creation time
+#[no_mangle]
+pub extern "C" fn create_lock_asset() {
+    let asset: Key = runtime::get_named_arg(ARG_ASSET);
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let lock_duration: u64 = runtime::get_named_arg(ARG_LOCK_DURATION);
+
+    when_not_paused(PAUSE_LOCKS, VeError::LocksPaused);
+    smart_wallet::assert_allowed_caller();
+    non_reentrant(|| {
+        _create_lock(
+            amount.as_u128(),
+            lock_duration,
+            utils::get_immediate_caller_key(),
+            asset,
+        );
+    });
 }
 
 #[no_mangle]
@@ -635,24 +1585,23 @@ pub extern "C" fn increase_amount() {
         VeError::NotOwnerOrApproved,
     );
 
-    when_not_locked();
-    lock_contract();
-
-    let ts = current_block_timestamp_seconds();
-    let __locked = get_locked_balance(token_id);
-    require(amount.as_u128() > 0, VeError::InvalidAmount);
-    require(__locked.amount > 0, VeError::NoExistingLock);
-    require(__locked.end > ts, VeError::CannotAddToExpiredLock);
-
-    _deposit_for(
-        token_id,
-        amount.as_u128(),
-        0,
-        &__locked,
-        INCREASE_LOCK_AMOUNT,
-    );
+    when_not_paused(PAUSE_DEPOSITS, VeError::DepositsPaused);
+    smart_wallet::assert_allowed_caller();
+    non_reentrant(|| {
+        let ts = current_block_timestamp_seconds();
+        let __locked = get_locked_balance(token_id);
+        require(amount.as_u128() > 0, VeError::InvalidAmount);
+        require(__locked.amount > 0 || __locked.is_permanent, VeError::NoExistingLock);
+        require(__locked.end > ts || __locked.is_permanent, VeError::CannotAddToExpiredLock);
 
-    unlock_contract();
+        _deposit_for(
+            token_id,
+            amount.as_u128(),
+            0,
+            &__locked,
+            INCREASE_LOCK_AMOUNT,
+        );
+    });
 }
 
 #[no_mangle]
@@ -665,24 +1614,22 @@ pub extern "C" fn increase_unlock_time() {
         VeError::NotOwnerOrApproved,
     );
 
-    when_not_locked();
-    lock_contract();
-
-    let ts = current_block_timestamp_seconds();
-    let __locked = get_locked_balance(token_id);
-    let unlock_time = (ts + lock_duration) / (WEEK as u64) * (WEEK as u64); // Locktime is rounded down to weeks
-
-    require(__locked.end > ts, VeError::CannotAddToExpiredLock);
-    require(__locked.amount > 0, VeError::NoExistingLock);
-    require(unlock_time > __locked.end, VeError::CanOnlyIncreaseLock);
-    require(
-        unlock_time <= ts + MAXTIME as u64,
-        VeError::VotingLockMax26Weeks,
-    );
+    when_not_paused(PAUSE_DEPOSITS, VeError::DepositsPaused);
+    non_reentrant(|| {
+        let ts = current_block_timestamp_seconds();
+        let __locked = get_locked_balance(token_id);
+        let unlock_time = (ts + lock_duration) / (WEEK as u64) * (WEEK as u64); // Locktime is rounded down to weeks
 
-    _deposit_for(token_id, 0, unlock_time, &__locked, INCREASE_UNLOCK_TIME);
+        require(__locked.end > ts, VeError::CannotAddToExpiredLock);
+        require(__locked.amount > 0, VeError::NoExistingLock);
+        require(unlock_time > __locked.end, VeError::CanOnlyIncreaseLock);
+        require(
+            unlock_time <= ts + MAXTIME as u64,
+            VeError::VotingLockMax26Weeks,
+        );
 
-    unlock_contract();
+        _deposit_for(token_id, 0, unlock_time, &__locked, INCREASE_UNLOCK_TIME);
+    });
 }
 
 fn _burn_nft(token_id: u64) {
@@ -692,15 +1639,238 @@ fn _burn_nft(token_id: u64) {
         VeError::NotOwnerOrApproved,
     );
     let owner = NFTToken::default().owner_of(token_id.into()).unwrap();
+    let delegatee = _token_delegatee(token_id);
     NFTToken::default()
         .burn(owner, vec![U256::from(token_id)])
         .unwrap_or_revert();
-    _move_token_delegates(owner, utils::null_key(), token_id);
-    _move_token_delegates(_delegates(owner), utils::null_key(), token_id);
+    _move_token_delegates(delegatee, utils::null_key(), token_id);
+    set_token_delegate(token_id, utils::null_key());
+}
+
+fn _push_asset(asset: Key, recipient: Key, amount: u128) {
+    if utils::is_null(asset) && native::is_enabled() {
+        native::transfer_out(recipient, amount);
+    } else {
+        erc20_helpers::transfer(_asset_token(asset), recipient, amount);
+    }
+}
+
+/// @notice Withdraw all tokens for `token_id`
+/// @dev Only possible if the lock has expired
+#[no_mangle]
+pub extern "C" fn withdraw() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    non_reentrant(|| {
+        let caller = utils::get_immediate_caller_key();
+        require(
+            NFTToken::default().is_approved_or_owner(token_id.into(), caller),
+            VeError::NotOwnerOrApproved,
+        );
+        require(
+            get_attachments(token_id) == 0 && !get_voted(token_id),
+            VeError::AttachedOrVoted,
+        );
+
+        require(_escrow_type(token_id) == ESCROW_TYPE_NORMAL, VeError::NotNormalNFT);
+        let locked = get_locked_balance(token_id);
+        require(!locked.is_permanent, VeError::LockPermanent);
+        require(
+            current_block_timestamp_seconds() >= locked.end,
+            VeError::LockNotExpired,
+        );
+        let value = locked.amount;
+
+        set_locked_balance(token_id, &LockedBalance::default());
+        _sub_locked_supply(locked.asset, value);
+
+        _check_point(token_id, &locked, &LockedBalance::default());
+        _burn_nft(token_id);
+        _push_asset(locked.asset, caller, value);
+    });
+}
+
+////////////////////////////////////////////////////////////////
+//                             MANAGED NFTS
+//////////////////////////////////////////////////////////////*/
+fn _escrow_type(token_id: u64) -> u8 {
+    Dict::instance(ESCROW_TYPE).get(&token_id.to_string()).unwrap_or(ESCROW_TYPE_NORMAL)
+}
+
+/// @notice `a * b / c` without overflowing the product
+fn _mul_div(a: u128, b: u128, c: u128) -> u128 {
+    erc20_helpers::to_u128(U256::from(a) * U256::from(b) / U256::from(c))
+}
+
+fn _managed_shares(managed_id: u64) -> u128 {
+    let shares: U128 = Dict::instance(MANAGED_SHARES)
+        .get(&managed_id.to_string())
+        .unwrap_or_default();
+    shares.as_u128()
+}
+
+/// @notice Mint a permanently locked veNFT that other locks can be deposited into
+/// @dev Rebases and `deposit_for` top-ups on the managed NFT accrue to its depositors
+/// @return The id of the managed NFT
+#[no_mangle]
+pub extern "C" fn create_managed_lock_for() {
+    let to: Key = runtime::get_named_arg(ARG_TO);
+    let managed_id = non_reentrant(|| {
+        only_team();
+        let managed_id = _mint_nft(to);
+        Dict::instance(ESCROW_TYPE).set(&managed_id.to_string(), ESCROW_TYPE_MANAGED);
+
+        let asset = utils::null_key();
+        let managed = LockedBalance {
+            asset,
+            weight: _asset_weight(asset),
+            is_permanent: true,
+            ..LockedBalance::default()
+        };
+        _deposit_for(managed_id, 0, 0, &managed, CREATE_LOCK_TYPE);
+
+        data::emit_ve(&VeEvent::CreateManaged {
+            to,
+            managed_id,
+            sender: utils::get_immediate_caller_key(),
+        });
+        managed_id
+    });
+    runtime::ret(CLValue::from_t(U256::from(managed_id)).unwrap_or_revert());
+}
+
+/// @notice Move the lock of `token_id` into the managed NFT `managed_id`
+/// @dev `token_id` stays with its owner as a receipt for the shares it was credited
+#[no_mangle]
+pub extern "C" fn deposit_managed() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let managed_id: u64 = runtime::get_named_arg::<U256>(ARG_MANAGED_ID).as_u64();
+    when_not_paused(PAUSE_DEPOSITS, VeError::DepositsPaused);
+    non_reentrant(|| {
+        let caller = utils::get_immediate_caller_key();
+        require(
+            NFTToken::default().is_approved_or_owner(token_id.into(), caller),
+            VeError::NotOwnerOrApproved,
+        );
+        require(_escrow_type(token_id) == ESCROW_TYPE_NORMAL, VeError::NotNormalNFT);
+        require(_escrow_type(managed_id) == ESCROW_TYPE_MANAGED, VeError::NotManagedNFT);
+        require(
+            get_attachments(token_id) == 0 && !get_voted(token_id),
+            VeError::AttachedOrVoted,
+        );
+
+        let locked = get_locked_balance(token_id);
+        let managed = get_locked_balance(managed_id);
+        require(locked.amount > 0, VeError::NoExistingLock);
+        require(
+            locked.end > current_block_timestamp_seconds(),
+            VeError::CannotAddToExpiredLock,
+        );
+        require(locked.asset == managed.asset, VeError::AssetMismatch);
+
+        // Shares are priced against the managed lock, which grows with its rebases
+        let total_shares = _managed_shares(managed_id);
+        let shares = _mul_div(
+            locked.amount,
+            total_shares + MANAGED_VIRTUAL_SHARES,
+            managed.amount + MANAGED_VIRTUAL_AMOUNT,
+        );
+        require(shares > 0, VeError::ZeroShares);
+
+        set_locked_balance(token_id, &LockedBalance::default());
+        _check_point(token_id, &locked, &LockedBalance::default());
+        _deposit_for(managed_id, locked.amount, 0, &managed, MERGE_TYPE);
+
+        Dict::instance(ESCROW_TYPE).set(&token_id.to_string(), ESCROW_TYPE_LOCKED);
+        Dict::instance(MANAGED_OF).set(&token_id.to_string(), (managed_id, U128::from(shares)));
+        Dict::instance(MANAGED_SHARES)
+            .set(&managed_id.to_string(), U128::from(total_shares + shares));
+
+        data::emit_ve(&VeEvent::DepositManaged {
+            owner: NFTToken::default().owner_of(token_id.into()).unwrap_or_revert(),
+            token_id,
+            managed_id,
+            amount: locked.amount,
+            shares,
+        });
+    });
+}
+
+/// @notice Take the share of `token_id` out of its managed NFT, rebases included
+/// @dev The amount is relocked in `token_id` for the maximum lock time
+#[no_mangle]
+pub extern "C" fn withdraw_managed() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    non_reentrant(|| {
+        let caller = utils::get_immediate_caller_key();
+        require(
+            NFTToken::default().is_approved_or_owner(token_id.into(), caller),
+            VeError::NotOwnerOrApproved,
+        );
+        require(_escrow_type(token_id) == ESCROW_TYPE_LOCKED, VeError::NotLockedNFT);
+
+        let (managed_id, shares): (u64, U128) = Dict::instance(MANAGED_OF)
+            .get(&token_id.to_string())
+            .unwrap_or_revert_with(VeError::NotLockedNFT);
+        let shares = shares.as_u128();
+        let total_shares = _managed_shares(managed_id);
+        let managed = get_locked_balance(managed_id);
+        let amount = _mul_div(
+            shares,
+            managed.amount + MANAGED_VIRTUAL_AMOUNT,
+            total_shares + MANAGED_VIRTUAL_SHARES,
+        );
+        let amount = core::cmp::min(amount, managed.amount);
+
+        let mut reduced = managed.clone();
+        reduced.amount = managed.amount - amount;
+        set_locked_balance(managed_id, &reduced);
+        _check_point(managed_id, &managed, &reduced);
+
+        let ts = current_block_timestamp_seconds();
+        let unlock_time = (ts + MAXTIME as u64) / (WEEK as u64) * (WEEK as u64);
+        let relocked = LockedBalance {
+            asset: managed.asset,
+            weight: managed.weight,
+            ..LockedBalance::default()
+        };
+        _deposit_for(token_id, amount, unlock_time, &relocked, MERGE_TYPE);
+
+        Dict::instance(MANAGED_SHARES)
+            .set(&managed_id.to_string(), U128::from(total_shares - shares));
+        Dict::instance(MANAGED_OF).remove::<(u64, U128)>(&token_id.to_string());
+        Dict::instance(ESCROW_TYPE).set(&token_id.to_string(), ESCROW_TYPE_NORMAL);
+
+        data::emit_ve(&VeEvent::WithdrawManaged {
+            owner: NFTToken::default().owner_of(token_id.into()).unwrap_or_revert(),
+            token_id,
+            managed_id,
+            amount,
+            shares,
+        });
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn escrow_type() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    runtime::ret(CLValue::from_t(_escrow_type(token_id)).unwrap_or_revert());
 }
 
+/// @return The managed NFT id and the shares held by the locked `token_id`, zeros otherwise
 #[no_mangle]
-pub extern "C" fn withdraw() {}
+pub extern "C" fn managed_of() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let managed: (u64, U128) = Dict::instance(MANAGED_OF)
+        .get(&token_id.to_string())
+        .unwrap_or_default();
+    runtime::ret(CLValue::from_t(managed).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn managed_shares() {
+    let managed_id: u64 = runtime::get_named_arg::<U256>(ARG_MANAGED_ID).as_u64();
+    runtime::ret(CLValue::from_t(U128::from(_managed_shares(managed_id))).unwrap_or_revert());
+}
 
 ////////////////////////////////////////////////////////////////
 //                             GAUGE VOTING STORAGE
@@ -772,6 +1942,23 @@ pub extern "C" fn balance_of_nft_at() {
     );
 }
 
+/// @notice Voting power of several NFTs at the same epoch time
+/// @param token_ids NFTs to query, at most MAX_BATCH_SIZE
+/// @param t Epoch time to return voting power at
+/// @return Voting power of each NFT, in the order of `token_ids`
+#[no_mangle]
+pub extern "C" fn balances_of_nft_at() {
+    let token_ids: Vec<U256> = runtime::get_named_arg(ARG_TOKEN_IDS);
+    let t: u64 = runtime::get_named_arg(ARG_T);
+    require(token_ids.len() <= MAX_BATCH_SIZE, VeError::BatchTooLarge);
+
+    let ret: Vec<U128> = token_ids
+        .iter()
+        .map(|token_id| U128::from(_balance_of_nft(token_id.as_u64(), t)))
+        .collect();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// @notice Measure voting power of `_tokenId` at block height `_block`
 /// @dev Adheres to MiniMe `balanceOfAt` interface: https://github.com/Giveth/minime
 /// @param _tokenId User's wallet NFT
@@ -889,7 +2076,7 @@ fn _supply_at(point: Point, t: u64) -> u128 {
             break;
         }
         last_point.slope = last_point.slope + d_slope;
-        last_point.ts = last_point.ts + t_i;
+        last_point.ts = t_i;
     }
 
     if last_point.bias < 0 {
@@ -904,6 +2091,132 @@ fn _total_supply_at_t(t: u64) -> u128 {
     _supply_at(last_point, t)
 }
 
+/// @notice Binary search for the last global epoch recorded at or before `ts`
+/// @param ts Timestamp to find
+/// @param max_epoch Don't go beyond this epoch
+/// @return Global epoch
+fn _find_timestamp_epoch(ts: u64, max_epoch: u64) -> u64 {
+    let mut _min = 0u64;
+    let mut _max = max_epoch;
+    for _i in 0..128 {
+        if _min >= _max {
+            break;
+        }
+        let _mid = (_min + _max + 1) / 2;
+        if get_point(_mid.into()).ts <= ts {
+            _min = _mid;
+        } else {
+            _max = _mid - 1;
+        }
+    }
+    _min
+}
+
+/// @notice Binary search for the last user epoch of `token_id` recorded at or before `ts`
+/// @param token_id NFT to search
+/// @param ts Timestamp to find
+/// @param max_user_epoch Don't go beyond this user epoch
+/// @return User epoch, 0 if the NFT had no lock yet
+fn _find_timestamp_user_epoch(token_id: u64, ts: u64, max_user_epoch: u64) -> u64 {
+    let mut _min = 0u64;
+    let mut _max = max_user_epoch;
+    for _i in 0..128 {
+        if _min >= _max {
+            break;
+        }
+        let _mid = (_min + _max + 1) / 2;
+        if get_user_point(token_id, _mid).ts <= ts {
+            _min = _mid;
+        } else {
+            _max = _mid - 1;
+        }
+    }
+    _min
+}
+
+/// @notice Number of samples in [from_ts, to_ts] taken every `step` seconds
+fn _curve_samples(from_ts: u64, to_ts: u64, step: u64) -> u64 {
+    require(step > 0 && from_ts <= to_ts, VeError::InvalidCurveRange);
+    let samples = (to_ts - from_ts) / step + 1;
+    require(samples <= MAX_CURVE_SAMPLES, VeError::TooManySamples);
+    samples
+}
+
+/// @notice Sample the total voting power between two timestamps
+/// @dev Walks point history forward once instead of searching it for each sample
+/// @param from_ts First sample time
+/// @param to_ts Last possible sample time
+/// @param step Seconds between two samples
+/// @return (ts, total voting power) pairs
+fn _supply_curve(from_ts: u64, to_ts: u64, step: u64) -> Vec<(u64, U128)> {
+    let samples = _curve_samples(from_ts, to_ts, step);
+    let max_epoch: u64 = get_key(EPOCH).unwrap_or(0);
+    let mut _epoch = _find_timestamp_epoch(from_ts, max_epoch);
+
+    let mut ret = Vec::new();
+    for i in 0..samples {
+        let t = from_ts + i * step;
+        while _epoch < max_epoch && get_point(_epoch as u128 + 1).ts <= t {
+            _epoch = _epoch + 1;
+        }
+        let point = get_point(_epoch as u128);
+        let value = if point.ts > t { 0 } else { _supply_at(point, t) };
+        ret.push((t, U128::from(value)));
+    }
+    ret
+}
+
+/// @notice Sample the voting power of `token_id` between two timestamps
+/// @dev Walks user point history forward once instead of searching it for each sample
+/// @param token_id NFT for lock
+/// @param from_ts First sample time
+/// @param to_ts Last possible sample time
+/// @param step Seconds between two samples
+/// @return (ts, voting power) pairs
+fn _nft_curve(token_id: u64, from_ts: u64, to_ts: u64, step: u64) -> Vec<(u64, U128)> {
+    let samples = _curve_samples(from_ts, to_ts, step);
+    let dict = Dict::instance(USER_POINT_EPOCH);
+    let max_user_epoch: u64 = dict.get(&token_id.to_string()).unwrap_or(0);
+    let mut user_epoch = _find_timestamp_user_epoch(token_id, from_ts, max_user_epoch);
+
+    let mut ret = Vec::new();
+    for i in 0..samples {
+        let t = from_ts + i * step;
+        while user_epoch < max_user_epoch && get_user_point(token_id, user_epoch + 1).ts <= t {
+            user_epoch = user_epoch + 1;
+        }
+        let mut value = 0u128;
+        if user_epoch != 0 {
+            let point = get_user_point(token_id, user_epoch);
+            let bias = point.bias - point.slope * (t as i128 - point.ts as i128);
+            if bias > 0 {
+                value = bias as u128;
+            }
+        }
+        ret.push((t, U128::from(value)));
+    }
+    ret
+}
+
+#[no_mangle]
+pub extern "C" fn supply_curve() {
+    let from_ts: u64 = runtime::get_named_arg(ARG_FROM_TS);
+    let to_ts: u64 = runtime::get_named_arg(ARG_TO_TS);
+    let step: u64 = runtime::get_named_arg(ARG_STEP);
+    runtime::ret(CLValue::from_t(_supply_curve(from_ts, to_ts, step)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn nft_curve() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let from_ts: u64 = runtime::get_named_arg(ARG_FROM_TS);
+    let to_ts: u64 = runtime::get_named_arg(ARG_TO_TS);
+    let step: u64 = runtime::get_named_arg(ARG_STEP);
+    runtime::ret(
+        CLValue::from_t(_nft_curve(token_id, from_ts, to_ts, step)).unwrap_or_revert(),
+    );
+}
+
 #[no_mangle]
 pub extern "C" fn ve_total_supply() {
     runtime::ret(
@@ -921,17 +2234,77 @@ pub extern "C" fn total_supply_at_t() {
 }
 
 ////////////////////////////////////////////////////////////////
-//                             GAUGE VOTING LOGIC
+//                             ENUMERATION
 //////////////////////////////////////////////////////////////*/
-fn voting_logic_init() {
-    storage::new_dictionary(ATTACHMENTS).unwrap_or_revert_with(VeError::FailedToCreateDictionary);
-    storage::new_dictionary(VOTED).unwrap_or_revert_with(VeError::FailedToCreateDictionary);
-}
+/// @notice Page through the NFTs held by `owner` together with their locks
+/// @param owner Account to enumerate
+/// @param offset Owned-token index to start from
+/// @param limit Maximum number of entries, capped at MAX_PAGE_SIZE
+/// @return (token id, locked balance, current voting power) for each NFT
+fn _tokens_of_owner(owner: Key, offset: u64, limit: u64) -> Vec<(U256, LockedBalance, U128)> {
+    let owned_tokens = OwnedTokens::instance();
+    let balance = owned_tokens.get_balances(&owner).as_u64();
+    let limit = core::cmp::min(limit, MAX_PAGE_SIZE);
+    let end = core::cmp::min(balance, offset.saturating_add(limit));
+    let ts = current_block_timestamp_seconds();
 
-fn get_attachments(token_id: u64) -> u64 {
-    let dict = Dict::instance(ATTACHMENTS);
-    let r: u64 = dict.get(&token_id.to_string()).unwrap_or(0);
-    r
+    let mut ret = Vec::new();
+    for i in offset..end {
+        let token_id = owned_tokens
+            .get_token_by_index(&owner, &U256::from(i))
+            .unwrap_or_revert();
+        let id = token_id.as_u64();
+        ret.push((
+            token_id,
+            get_locked_balance(id),
+            U128::from(_balance_of_nft(id, ts)),
+        ));
+    }
+    ret
+}
+
+#[no_mangle]
+pub extern "C" fn tokens_of_owner() {
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    runtime::ret(CLValue::from_t(_tokens_of_owner(owner, offset, limit)).unwrap_or_revert());
+}
+
+/// @notice Sum of the current voting power of every NFT held by `owner`
+fn _account_voting_power(owner: Key) -> u128 {
+    let owned_tokens = OwnedTokens::instance();
+    let balance = owned_tokens.get_balances(&owner).as_u64();
+    let ts = current_block_timestamp_seconds();
+
+    let mut power = 0u128;
+    for i in 0..balance {
+        let token_id = owned_tokens
+            .get_token_by_index(&owner, &U256::from(i))
+            .unwrap_or_revert();
+        power = power + _balance_of_nft(token_id.as_u64(), ts);
+    }
+    power
+}
+
+#[no_mangle]
+pub extern "C" fn account_voting_power() {
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    runtime::ret(CLValue::from_t(U128::from(_account_voting_power(owner))).unwrap_or_revert());
+}
+
+////////////////////////////////////////////////////////////////
+//                             GAUGE VOTING LOGIC
+//////////////////////////////////////////////////////////////*/
+fn voting_logic_init() {
+    storage::new_dictionary(ATTACHMENTS).unwrap_or_revert_with(VeError::FailedToCreateDictionary);
+    storage::new_dictionary(VOTED).unwrap_or_revert_with(VeError::FailedToCreateDictionary);
+}
+
+fn get_attachments(token_id: u64) -> u64 {
+    let dict = Dict::instance(ATTACHMENTS);
+    let r: u64 = dict.get(&token_id.to_string()).unwrap_or(0);
+    r
 }
 
 fn get_voted(token_id: u64) -> bool {
@@ -941,48 +2314,89 @@ fn get_voted(token_id: u64) -> bool {
 }
 
 fn only_voter() {
+    roles::only_role(VOTER_ROLE, VeError::NotVoter);
+}
+
+fn only_team_or_voter() {
     let caller = utils::get_immediate_caller_key();
-    let voter: Key = get_key(VOTER).unwrap();
-    require(caller == voter, VeError::NotVoter);
+    require(
+        roles::has_role(TEAM_ROLE, caller) || roles::has_role(VOTER_ROLE, caller),
+        VeError::NotTeamOrVoter,
+    );
+}
+
+/// @notice Queue proposing `new_voter` as voter, either the team or the current voter may propose
+/// @dev Once executed nothing changes until `new_voter` calls `accept_voter`
+/// @return The id of the queued operation
+#[no_mangle]
+pub extern "C" fn propose_voter() {
+    let new_voter: Key = runtime::get_named_arg(ARG_NEW_VOTER);
+    let op_id = non_reentrant(|| {
+        only_team_or_voter();
+        timelock::queue(OP_PROPOSE_VOTER, new_voter, 0)
+    });
+    runtime::ret(CLValue::from_t(op_id).unwrap_or_revert());
 }
 
 #[no_mangle]
-pub extern "C" fn set_voter() {
-    let voter: Key = runtime::get_named_arg(VOTER);
-    only_voter();
-    set_key(VOTER, voter);
+pub extern "C" fn accept_voter() {
+    non_reentrant(|| {
+        roles::accept_transfer(VOTER_ROLE);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn cancel_voter() {
+    non_reentrant(|| {
+        only_team_or_voter();
+        roles::cancel_transfer(VOTER_ROLE);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn pending_voter() {
+    let pending = roles::pending_transfer(VOTER_ROLE).map(|(to, _)| to);
+    runtime::ret(CLValue::from_t(pending).unwrap_or_revert());
 }
 
 #[no_mangle]
 pub extern "C" fn voting() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
-    only_voter();
-    let dict = Dict::instance(VOTED);
-    dict.set(&token_id.to_string(), true);
+    non_reentrant(|| {
+        only_voter();
+        let dict = Dict::instance(VOTED);
+        dict.set(&token_id.to_string(), true);
+    });
 }
 
 #[no_mangle]
 pub extern "C" fn abstain() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
-    only_voter();
-    let dict = Dict::instance(VOTED);
-    dict.set(&token_id.to_string(), false);
+    non_reentrant(|| {
+        only_voter();
+        let dict = Dict::instance(VOTED);
+        dict.set(&token_id.to_string(), false);
+    });
 }
 
 #[no_mangle]
 pub extern "C" fn attach() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
-    only_voter();
-    let dict = Dict::instance(ATTACHMENTS);
-    dict.set(&token_id.to_string(), token_id + 1);
+    non_reentrant(|| {
+        only_voter();
+        let dict = Dict::instance(ATTACHMENTS);
+        dict.set(&token_id.to_string(), get_attachments(token_id) + 1);
+    });
 }
 
 #[no_mangle]
 pub extern "C" fn detach() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
-    only_voter();
-    let dict = Dict::instance(ATTACHMENTS);
-    dict.set(&token_id.to_string(), token_id - 1);
+    non_reentrant(|| {
+        only_voter();
+        let dict = Dict::instance(ATTACHMENTS);
+        dict.set(&token_id.to_string(), get_attachments(token_id) - 1);
+    });
 }
 
 #[no_mangle]
@@ -1001,20 +2415,36 @@ pub extern "C" fn merge() {
         VeError::NotOwnerOrApproved,
     );
 
-    let locked0 = get_locked_balance(from);
-    let locked1 = get_locked_balance(to);
-    let value0 = locked0.amount as u128;
-    let end = if locked0.end >= locked1.end {
-        locked0.end
-    } else {
-        locked1.end
-    };
+    require(
+        _escrow_type(from) == ESCROW_TYPE_NORMAL && _escrow_type(to) == ESCROW_TYPE_NORMAL,
+        VeError::NotNormalNFT,
+    );
 
-    let dict = Dict::instance(LOCKED);
-    dict.set(&from.to_string(), LockedBalance::default());
-    _check_point(from, &locked0, &LockedBalance::default());
-    _burn_nft(from);
-    _deposit_for(to, value0, end, &locked1, MERGE_TYPE);
+    when_not_paused(PAUSE_MERGES, VeError::MergesPaused);
+    non_reentrant(|| {
+        let locked0 = get_locked_balance(from);
+        let locked1 = get_locked_balance(to);
+        require(locked0.asset == locked1.asset, VeError::AssetMismatch);
+        let value0 = locked0.amount as u128;
+        let end = if locked0.end >= locked1.end {
+            locked0.end
+        } else {
+            locked1.end
+        };
+
+        set_locked_balance(from, &LockedBalance::default());
+        _check_point(from, &locked0, &LockedBalance::default());
+        _burn_nft(from);
+
+        // Never let a merge raise the weight the merged amount was locked at. The old
+        // lock of `to` is checkpointed as it was, only the merged lock takes the new weight.
+        let mut merged = locked1.clone();
+        merged.amount = locked1.amount + value0;
+        merged.end = end;
+        merged.weight = core::cmp::min(locked0.weight, locked1.weight);
+        set_locked_balance(to, &merged);
+        _check_point(to, &locked1, &merged);
+    });
 }
 
 ////////////////////////////////////////////////////////////////
@@ -1026,6 +2456,7 @@ fn dao_voting_storage_init() {
     storage::new_dictionary(NUM_CHECKPOINTS)
         .unwrap_or_revert_with(VeError::FailedToCreateDictionary);
     storage::new_dictionary(NONCES).unwrap_or_revert_with(VeError::FailedToCreateDictionary);
+    Dict::init(TOKEN_DELEGATES);
 }
 
 fn get_delegate(a: Key) -> Key {
@@ -1042,6 +2473,16 @@ fn set_delegate(a: Key, d: Key) {
     dict.set(&k, d);
 }
 
+fn get_token_delegate(token_id: u64) -> Key {
+    let dict = Dict::instance(TOKEN_DELEGATES);
+    dict.get(&token_id.to_string()).unwrap_or(utils::null_key())
+}
+
+fn set_token_delegate(token_id: u64, d: Key) {
+    let dict = Dict::instance(TOKEN_DELEGATES);
+    dict.set(&token_id.to_string(), d);
+}
+
 fn get_check_point_key(a: Key, index: u64) -> String {
     let k = a.to_bytes().unwrap();
 
@@ -1102,10 +2543,28 @@ fn _delegates(delegator: Key) -> Key {
     current
 }
 
+/// @notice The account whose votes `token_id` counts towards
+/// @dev A per-token delegate takes precedence over the delegate of the owner
+fn _token_delegatee(token_id: u64) -> Key {
+    let delegatee = get_token_delegate(token_id);
+    if utils::is_not_null(delegatee) {
+        return delegatee;
+    }
+    let owner = NFTToken::default().owner_of(token_id.into()).unwrap_or_revert();
+    _delegates(owner)
+}
+
 #[no_mangle]
 pub extern "C" fn delegates() {
     let delegator: Key = runtime::get_named_arg(DELEGATOR);
-    runtime::ret(CLValue::from_t(delegator).unwrap_or_revert());
+    runtime::ret(CLValue::from_t(_delegates(delegator)).unwrap_or_revert());
+}
+
+/// @return The account the votes of `token_id` are currently delegated to
+#[no_mangle]
+pub extern "C" fn token_delegate() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    runtime::ret(CLValue::from_t(_token_delegatee(token_id)).unwrap_or_revert());
 }
 
 /**
@@ -1122,7 +2581,7 @@ pub extern "C" fn get_votes() {
     if n_checkpoints == 0 {
         ret = 0;
     } else {
-        let _token_ids = get_check_point(account, n_checkpoints).token_ids;
+        let _token_ids = _current_delegated_ids(account);
         for i in 0.._token_ids.len() {
             let id = _token_ids[i];
             ret = ret + _balance_of_nft(id, ts);
@@ -1170,10 +2629,7 @@ pub extern "C" fn get_past_votes_index() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
-#[no_mangle]
-pub extern "C" fn get_past_votes() {
-    let account: Key = runtime::get_named_arg(ARG_ADDRESS);
-    let timestamp: u64 = runtime::get_named_arg(ARG_TIMESTAMP);
+fn _get_past_votes(account: Key, timestamp: u64) -> u128 {
     let _check_index = _get_past_votes_index(account, timestamp);
     // Sum votes
     let _token_ids = get_check_point(account, _check_index).token_ids;
@@ -1182,7 +2638,31 @@ pub extern "C" fn get_past_votes() {
         // Use the provided input timestamp here to get the right decay
         votes = votes + _balance_of_nft(tid, timestamp);
     }
-    runtime::ret(CLValue::from_t(U128::from(votes)).unwrap_or_revert());
+    votes
+}
+
+#[no_mangle]
+pub extern "C" fn get_past_votes() {
+    let account: Key = runtime::get_named_arg(ARG_ADDRESS);
+    let timestamp: u64 = runtime::get_named_arg(ARG_TIMESTAMP);
+    runtime::ret(CLValue::from_t(U128::from(_get_past_votes(account, timestamp))).unwrap_or_revert());
+}
+
+/// @notice Past votes of several accounts at the same timestamp
+/// @param accounts Accounts to query, at most MAX_BATCH_SIZE
+/// @param timestamp The timestamp to return the votes at
+/// @return Votes of each account, in the order of `accounts`
+#[no_mangle]
+pub extern "C" fn past_votes_batch() {
+    let accounts: Vec<Key> = runtime::get_named_arg(ARG_ACCOUNTS);
+    let timestamp: u64 = runtime::get_named_arg(ARG_TIMESTAMP);
+    require(accounts.len() <= MAX_BATCH_SIZE, VeError::BatchTooLarge);
+
+    let ret: Vec<U128> = accounts
+        .iter()
+        .map(|account| U128::from(_get_past_votes(*account, timestamp)))
+        .collect();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
 #[no_mangle]
@@ -1195,49 +2675,46 @@ pub extern "C" fn get_past_total_supply() {
 ////////////////////////////////////////////////////////////////
 //                             DAO VOTING LOGIC
 //////////////////////////////////////////////////////////////*/
+/// @notice Latest delegated token ids of `account`
+fn _current_delegated_ids(account: Key) -> Vec<u64> {
+    let n_checkpoints = get_num_checkpoints(account);
+    if n_checkpoints > 0 {
+        get_check_point(account, n_checkpoints - 1).token_ids
+    } else {
+        Vec::new()
+    }
+}
+
+/// @notice Record `token_ids` as the delegated set of `account` from now on,
+///         replacing a checkpoint already written at this timestamp
+fn _write_delegate_checkpoint(account: Key, token_ids: Vec<u64>) {
+    let n_checkpoints = get_num_checkpoints(account);
+    let index = _find_what_checkpoint_to_write(account);
+    let cp = Checkpoint {
+        timestamp: current_block_timestamp_seconds() as u128,
+        token_ids,
+    };
+    set_check_point(account, index, &cp);
+    if index == n_checkpoints {
+        set_num_checkpoints(account, n_checkpoints + 1);
+    }
+}
+
+/// @dev Uncapped, so mints and transfers always reach the delegate of the new owner.
+///      Delegations by choice are capped by `_require_delegate_room` beforehand.
 pub(crate) fn _move_token_delegates(src: Key, dst: Key, token_id: u64) {
-    runtime::print("hehre");
     if src != dst && token_id > 0 {
         if utils::is_not_null(src) {
-            let src_rep_num = get_num_checkpoints(src);
-            let cp = if src_rep_num > 0 {
-                get_check_point(src, src_rep_num - 1)
-            } else {
-                get_check_point(src, 0)
-            };
-
-            let next_src_rep_num = _find_what_checkpoint_to_write(src);
-            let mut cp_new = get_check_point(src, next_src_rep_num);
-            for i in 0..cp.token_ids.len() {
-                let id = cp.token_ids[i];
-                if id != token_id {
-                    cp_new.token_ids.push(id);
-                }
-            }
-            set_check_point(src, next_src_rep_num, &cp_new);
-            set_num_checkpoints(src, src_rep_num + 1);
+            let mut src_ids = _current_delegated_ids(src);
+            src_ids.retain(|id| *id != token_id);
+            _write_delegate_checkpoint(src, src_ids);
         }
 
         if utils::is_not_null(dst) {
-            let dst_rep_num = get_num_checkpoints(dst);
-            let cp = if dst_rep_num > 0 {
-                get_check_point(dst, dst_rep_num - 1)
-            } else {
-                get_check_point(dst, 0)
-            };
-
-            require(cp.token_ids.len() + 1 <= MAX_DELEGATES as usize, VeError::TooManyTokenIds);
-
-            let next_dst_rep_num = _find_what_checkpoint_to_write(dst);
-            let mut cp_new = get_check_point(dst, next_dst_rep_num);
-            for i in 0..cp.token_ids.len() {
-                let id = cp.token_ids[i];
-                if id != token_id {
-                    cp_new.token_ids.push(id);
-                }
-            }
-            set_check_point(dst, next_dst_rep_num, &cp_new);
-            set_num_checkpoints(dst, dst_rep_num + 1);
+            let mut dst_ids = _current_delegated_ids(dst);
+            dst_ids.retain(|id| *id != token_id);
+            dst_ids.push(token_id);
+            _write_delegate_checkpoint(dst, dst_ids);
         }
     }
 }
@@ -1254,65 +2731,104 @@ fn _find_what_checkpoint_to_write(account: Key) -> u64 {
     }
 }
 
+/// @notice Delegating into the set of another account may grow it to MAX_DELEGATES
+/// @dev Anyone can delegate a dust lock to anyone, the cap keeps that from growing a
+///      set without bound. An owner taking its tokens back into its own set is never
+///      refused, so such dust can't lock the owner out of its own votes.
+fn _require_delegate_room(delegatee: Key, owner: Key, new_len: usize) {
+    if delegatee != owner {
+        require(new_len <= MAX_DELEGATES as usize, VeError::TooManyTokenIds);
+    }
+}
+
+/// @notice Move the tokens of `owner` that follow its account-level delegate
+/// @dev Tokens delegated on their own keep their delegate
 fn _move_all_delegates(owner: Key, src: Key, dst: Key) {
     if src != dst {
-        if utils::is_not_null(src) {
-            let src_rep_num = get_num_checkpoints(src);
-            let src_rep_old = if src_rep_num > 0 {
-                get_check_point(src, src_rep_num - 1)
-            } else {
-                get_check_point(src, 0)
-            };
+        let follows_owner = |tid: u64| {
+            utils::is_null(get_token_delegate(tid))
+                && NFTToken::default().owner_of(tid.into()).unwrap() == owner
+        };
 
-            let next_src_rep_num = _find_what_checkpoint_to_write(src);
-            let mut src_rep_new = get_check_point(src, next_src_rep_num);
-
-            for tid in &src_rep_old.token_ids {
-                if NFTToken::default().owner_of((*tid).into()).unwrap() != owner {
-                    src_rep_new.token_ids.push(*tid);
-                }
-            }
-            set_check_point(src, next_src_rep_num, &src_rep_new);
-            set_num_checkpoints(src, src_rep_num + 1);
+        if utils::is_not_null(src) {
+            let mut src_ids = _current_delegated_ids(src);
+            src_ids.retain(|tid| !follows_owner(*tid));
+            _write_delegate_checkpoint(src, src_ids);
         }
 
         if utils::is_not_null(dst) {
-            let dst_rep_num = get_num_checkpoints(dst);
-            let dst_rep_old = if dst_rep_num > 0 {
-                get_check_point(dst, dst_rep_num - 1)
-            } else {
-                get_check_point(dst, 0)
-            };
-            let next_dst_rep_num = _find_what_checkpoint_to_write(dst);
-            let mut dst_rep_new = get_check_point(dst, next_dst_rep_num);
+            let mut dst_ids = _current_delegated_ids(dst);
             let owner_token_count = NFTToken::default().balance_of(owner).as_usize();
-            require(dst_rep_old.token_ids.len() + owner_token_count <= MAX_DELEGATES as usize, VeError::TooManyTokenIds);
-            for tid in &dst_rep_old.token_ids {
-                dst_rep_new.token_ids.push(*tid);
-            }
-
+            _require_delegate_room(dst, owner, dst_ids.len() + owner_token_count);
             for i in 0..owner_token_count {
                 let tid = NFTToken::default().get_token_by_index(owner, U256::from(i)).unwrap().as_u64();
-                dst_rep_new.token_ids.push(tid);
+                if follows_owner(tid) && !dst_ids.contains(&tid) {
+                    dst_ids.push(tid);
+                }
             }
-            set_check_point(dst, next_dst_rep_num, &dst_rep_new);
-            set_num_checkpoints(dst, dst_rep_num + 1);
+            _write_delegate_checkpoint(dst, dst_ids);
         }
     }
 }
 
 fn _delegate(delegator: Key, delegatee: Key) {
-    let current_delegate = get_delegate(delegator);
+    // Undelegated tokens are checkpointed under the delegator itself
+    let current_delegate = _delegates(delegator);
     set_delegate(delegator, delegatee);
 
-    _move_all_delegates(delegator, current_delegate, delegatee);
+    _move_all_delegates(delegator, current_delegate, _delegates(delegator));
+}
+
+/// @notice Delegate the votes of `token_id` to `delegatee`
+/// @dev A null `delegatee` makes the token follow the delegate of its owner again
+fn _delegate_token(token_id: u64, delegatee: Key) {
+    let current_delegate = _token_delegatee(token_id);
+    set_token_delegate(token_id, delegatee);
+
+    let new_delegate = _token_delegatee(token_id);
+    if new_delegate != current_delegate && utils::is_not_null(new_delegate) {
+        let owner = NFTToken::default().owner_of(token_id.into()).unwrap_or_revert();
+        _require_delegate_room(
+            new_delegate,
+            owner,
+            _current_delegated_ids(new_delegate).len() + 1,
+        );
+    }
+    _move_token_delegates(current_delegate, new_delegate, token_id);
+}
+
+/// @notice Hand the votes of `token_id` to the delegate of `to` ahead of a transfer
+/// @dev A per-token delegation is chosen by the owner, so it doesn't survive a transfer
+pub fn _transfer_delegates(token_id: u64, to: Key) {
+    let current_delegate = _token_delegatee(token_id);
+    set_token_delegate(token_id, utils::null_key());
+
+    _move_token_delegates(current_delegate, _delegates(to), token_id);
 }
 
 #[no_mangle]
 pub extern "C" fn delegate() {
-    let delegatee: Key = runtime::get_named_arg("delegatee");
+    let delegatee: Key = runtime::get_named_arg(ARG_DELEGATEE);
     let caller = utils::get_immediate_caller_key();
-    _delegate(caller, delegatee);
+    when_not_paused(PAUSE_DELEGATION, VeError::DelegationPaused);
+    non_reentrant(|| {
+        _delegate(caller, delegatee);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn delegate_token() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let delegatee: Key = runtime::get_named_arg(ARG_DELEGATEE);
+    let caller = utils::get_immediate_caller_key();
+    when_not_paused(PAUSE_DELEGATION, VeError::DelegationPaused);
+    non_reentrant(|| {
+        require(
+            NFTToken::default().is_approved_or_owner(token_id.into(), caller),
+            VeError::NotOwnerOrApproved,
+        );
+        _delegate_token(token_id, delegatee);
+    });
 }
 
 #[no_mangle]
@@ -1323,20 +2839,43 @@ pub extern "C" fn increase_amount_for() {
     let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
     let amount: u128 = runtime::get_named_arg::<U128>(ARG_AMOUNT).as_u128();
 
-    let caller = utils::get_immediate_caller_key();
-    require(caller == get_key::<Key>(TEAM).unwrap(), VeError::NOTTEAM);
+    only_team();
 
-    let locked = get_locked_balance(token_id);
-    require(amount > 0, VeError::InvalidAmount);
+    when_not_paused(PAUSE_DEPOSITS, VeError::DepositsPaused);
+    smart_wallet::assert_allowed_caller();
+    non_reentrant(|| {
+        let locked = get_locked_balance(token_id);
+        require(amount > 0, VeError::InvalidAmount);
 
-    require(locked.amount > 0, VeError::NoExistingLock);
-    require(locked.end > current_block_timestamp_seconds(), VeError::CannotAddToExpiredLock);
+        require(locked.amount > 0, VeError::NoExistingLock);
+        require(locked.end > current_block_timestamp_seconds(), VeError::CannotAddToExpiredLock);
 
-    _deposit_for(token_id, amount, 0, &locked, INCREASE_LOCK_AMOUNT);
+        _deposit_for(token_id, amount, 0, &locked, INCREASE_LOCK_AMOUNT);
+    });
 }
 
 pub fn get_entry_points() -> EntryPoints {
     let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "tokens_of_owner",
+        vec![
+            Parameter::new(ARG_OWNER, Key::cl_type()),
+            Parameter::new(ARG_OFFSET, u64::cl_type()),
+            Parameter::new(ARG_LIMIT, u64::cl_type()),
+        ],
+        Vec::<(U256, LockedBalance, U128)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "account_voting_power",
+        vec![Parameter::new(ARG_OWNER, Key::cl_type())],
+        CLType::U128,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "increase_amount_for",
         vec![
@@ -1370,6 +2909,17 @@ pub fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "past_votes_batch",
+        vec![
+            Parameter::new(ARG_ACCOUNTS, Vec::<Key>::cl_type()),
+            Parameter::new(ARG_TIMESTAMP, u64::cl_type()),
+        ],
+        Vec::<U128>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "get_past_total_supply",
         vec![
@@ -1382,7 +2932,7 @@ pub fn get_entry_points() -> EntryPoints {
 
     entry_points.add_entry_point(EntryPoint::new(
         "delegate",
-        vec![Parameter::new("delegatee", Key::cl_type())],
+        vec![Parameter::new(ARG_DELEGATEE, Key::cl_type())],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -1404,6 +2954,25 @@ pub fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "delegate_token",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_DELEGATEE, Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "token_delegate",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "merge",
         vec![
@@ -1448,13 +3017,37 @@ pub fn get_entry_points() -> EntryPoints {
     ));
 
     entry_points.add_entry_point(EntryPoint::new(
-        "set_voter",
-        vec![Parameter::new(VOTER, Key::cl_type())],
+        "propose_voter",
+        vec![Parameter::new(ARG_NEW_VOTER, Key::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "accept_voter",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_voter",
+        vec![],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "pending_voter",
+        vec![],
+        CLType::Option(Box::new(CLType::Key)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "total_supply_at_t",
         vec![Parameter::new(ARG_T, u64::cl_type())],
@@ -1463,6 +3056,31 @@ pub fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "supply_curve",
+        vec![
+            Parameter::new(ARG_FROM_TS, u64::cl_type()),
+            Parameter::new(ARG_TO_TS, u64::cl_type()),
+            Parameter::new(ARG_STEP, u64::cl_type()),
+        ],
+        Vec::<(u64, U128)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "nft_curve",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_FROM_TS, u64::cl_type()),
+            Parameter::new(ARG_TO_TS, u64::cl_type()),
+            Parameter::new(ARG_STEP, u64::cl_type()),
+        ],
+        Vec::<(u64, U128)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "ve_total_supply",
         vec![],
@@ -1501,6 +3119,17 @@ pub fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "balances_of_nft_at",
+        vec![
+            Parameter::new(ARG_TOKEN_IDS, Vec::<U256>::cl_type()),
+            Parameter::new(ARG_T, u64::cl_type()),
+        ],
+        Vec::<U128>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "balance_of_nft",
         vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
@@ -1518,6 +3147,57 @@ pub fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_managed_lock_for",
+        vec![Parameter::new(ARG_TO, Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit_managed",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_MANAGED_ID, U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_managed",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "escrow_type",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "managed_of",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        <(u64, U128)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "managed_shares",
+        vec![Parameter::new(ARG_MANAGED_ID, U256::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         "increase_unlock_time",
         vec![
@@ -1583,27 +3263,102 @@ pub fn get_entry_points() -> EntryPoints {
     ));
 
     entry_points.add_entry_point(EntryPoint::new(
-        "locked_end",
-        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
-        CLType::U64,
+        "check_point_partial",
+        vec![Parameter::new(ARG_MAX_WEEKS, u64::cl_type())],
+        CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
     entry_points.add_entry_point(EntryPoint::new(
-        "user_point_history__ts",
-        vec![
-            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
-            Parameter::new(EPOCH_INDEX, u64::cl_type()),
-        ],
-        CLType::U128,
+        "check_point_pending_weeks",
+        vec![],
+        CLType::U64,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
     entry_points.add_entry_point(EntryPoint::new(
-        "get_last_user_slope",
-        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        "set_checkpoint_max_weeks",
+        vec![Parameter::new(ARG_MAX_WEEKS, u64::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "locked_end",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "locked_is_permanent",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "locked_asset",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        CLType::Key,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "user_point_history__ts",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(EPOCH_INDEX, u64::cl_type()),
+        ],
+        CLType::U128,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "user_point_epoch",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "user_point_history",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(EPOCH_INDEX, u64::cl_type()),
+        ],
+        Point::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "point_history",
+        vec![Parameter::new(EPOCH_INDEX, u64::cl_type())],
+        Point::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "epoch",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_last_user_slope",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
         I128::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -1612,18 +3367,303 @@ pub fn get_entry_points() -> EntryPoints {
     entry_points.add_entry_point(EntryPoint::new(
         "set_art_proxy",
         vec![Parameter::new("new_art_proxy", Key::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "pause",
+        vec![Parameter::new(ARG_FLAGS, u8::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "unpause",
+        vec![Parameter::new(ARG_FLAGS, u8::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "paused_flags",
+        vec![],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_lock_asset",
+        vec![
+            Parameter::new(ARG_ASSET, Key::cl_type()),
+            Parameter::new(ARG_LOCK_DURATION, u64::cl_type()),
+            Parameter::new(ARG_AMOUNT, U128::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_asset_weight",
+        vec![
+            Parameter::new(ARG_ASSET, Key::cl_type()),
+            Parameter::new(ARG_WEIGHT, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "asset_weight",
+        vec![Parameter::new(ARG_ASSET, Key::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "asset_supply",
+        vec![Parameter::new(ARG_ASSET, Key::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "solvency",
+        vec![],
+        SolvencyReport::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "rescue_token",
+        vec![
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_RECIPIENT, Key::cl_type()),
+            Parameter::new(ARG_AMOUNT, U128::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "execute_queued",
+        vec![Parameter::new(ARG_OP_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_queued",
+        vec![Parameter::new(ARG_OP_ID, u64::cl_type())],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
     entry_points.add_entry_point(EntryPoint::new(
-        "set_team",
-        vec![Parameter::new("new_team", Key::cl_type())],
+        "set_timelock_delay",
+        vec![Parameter::new(ARG_DELAY, u64::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_strict_transfers",
+        vec![Parameter::new(ARG_ENABLED, bool::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "timelock_delay",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "queued_operation",
+        vec![Parameter::new(ARG_OP_ID, u64::cl_type())],
+        QueuedOperation::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "queued_operations",
+        vec![
+            Parameter::new(ARG_OFFSET, u64::cl_type()),
+            Parameter::new(ARG_LIMIT, u64::cl_type()),
+        ],
+        Vec::<(u64, QueuedOperation)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_smart_wallet_check",
+        vec![Parameter::new(ARG_ENABLED, bool::cl_type())],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        "allow_smart_wallet",
+        vec![Parameter::new(ARG_ADDRESS, Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "disallow_smart_wallet",
+        vec![Parameter::new(ARG_ADDRESS, Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_smart_wallet_checker",
+        vec![Parameter::new(ARG_CHECKER, Option::<Key>::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_smart_wallet_allowed",
+        vec![Parameter::new(ARG_ADDRESS, Key::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grant_role",
+        vec![
+            Parameter::new(ARG_ROLE, String::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "revoke_role",
+        vec![
+            Parameter::new(ARG_ROLE, String::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "queue_grant_role",
+        vec![
+            Parameter::new(ARG_ROLE, String::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "queue_revoke_role",
+        vec![
+            Parameter::new(ARG_ROLE, String::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "renounce_role",
+        vec![Parameter::new(ARG_ROLE, String::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "has_role",
+        vec![
+            Parameter::new(ARG_ROLE, String::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "migrate",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "schema_version",
+        vec![],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_team",
+        vec![Parameter::new(ARG_NEW_TEAM, Key::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "accept_team",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_team",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "pending_team",
+        vec![],
+        CLType::Option(Box::new(CLType::Key)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points
 }
@@ -0,0 +1,27 @@
+//! Calls into the bribe of a pool, which records the votes its rewards are shared by
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128, U256};
+
+fn package(bribe: Key) -> ContractPackageHash {
+    ContractPackageHash::new(bribe.into_hash().unwrap_or_revert())
+}
+
+fn call(bribe: Key, entry_point: &str, amount: u128, token_id: u64) {
+    let _: () = runtime::call_versioned_contract(
+        package(bribe),
+        None,
+        entry_point,
+        runtime_args! {
+            "amount" => U128::from(amount),
+            "token_id" => U256::from(token_id)
+        },
+    );
+}
+
+pub fn deposit(bribe: Key, amount: u128, token_id: u64) {
+    call(bribe, "deposit", amount, token_id);
+}
+
+pub fn withdraw(bribe: Key, amount: u128, token_id: u64) {
+    call(bribe, "withdraw", amount, token_id);
+}
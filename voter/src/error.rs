@@ -0,0 +1,25 @@
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy)]
+pub enum VoterError {
+    NotTeam = 1,
+    NotOwnerOrApproved = 2,
+    AlreadyVotedThisEpoch = 3,
+    UnknownPool = 4,
+    GaugeExists = 5,
+    NotGauge = 6,
+    LengthMismatch = 7,
+    TooManyPools = 8,
+    DuplicateVote = 9,
+    ZeroVote = 10,
+    WeightOverflow = 11,
+    VoteExceedsBalance = 12,
+    UnknownTokenStandard = 13,
+}
+
+impl From<VoterError> for ApiError {
+    fn from(e: VoterError) -> Self {
+        ApiError::User(e as u16)
+    }
+}
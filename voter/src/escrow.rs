@@ -0,0 +1,88 @@
+//! Calls into the voting escrow, addressed by its package hash so escrow upgrades are followed.
+//! `voting`, `abstain`, `attach` and `detach` require this contract to hold the escrow's voter role.
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128, U256};
+
+fn package(escrow: Key) -> ContractPackageHash {
+    ContractPackageHash::new(escrow.into_hash().unwrap_or_revert())
+}
+
+pub fn owner_of(escrow: Key, token_id: u64) -> Option<Key> {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "owner_of",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn get_approved(escrow: Key, owner: Key, token_id: u64) -> Option<Key> {
+    runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "get_approved",
+        runtime_args! {
+            "owner" => owner,
+            "token_id" => U256::from(token_id)
+        },
+    )
+}
+
+pub fn is_approved_or_owner(escrow: Key, spender: Key, token_id: u64) -> bool {
+    match owner_of(escrow, token_id) {
+        Some(owner) if owner == spender => true,
+        Some(owner) => get_approved(escrow, owner, token_id) == Some(spender),
+        None => false,
+    }
+}
+
+pub fn balance_of_nft(escrow: Key, token_id: u64) -> u128 {
+    let balance: U128 = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "balance_of_nft",
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    );
+    balance.as_u128()
+}
+
+/// @notice Take over the escrow's voter role once the escrow proposed this contract
+pub fn accept_voter(escrow: Key) {
+    let _: () = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        "accept_voter",
+        runtime_args! {},
+    );
+}
+
+fn call_hook(escrow: Key, entry_point: &str, token_id: u64) {
+    let _: () = runtime::call_versioned_contract(
+        package(escrow),
+        None,
+        entry_point,
+        runtime_args! {
+            "token_id" => U256::from(token_id)
+        },
+    );
+}
+
+pub fn voting(escrow: Key, token_id: u64) {
+    call_hook(escrow, "voting", token_id);
+}
+
+pub fn abstain(escrow: Key, token_id: u64) {
+    call_hook(escrow, "abstain", token_id);
+}
+
+pub fn attach(escrow: Key, token_id: u64) {
+    call_hook(escrow, "attach", token_id);
+}
+
+pub fn detach(escrow: Key, token_id: u64) {
+    call_hook(escrow, "detach", token_id);
+}
@@ -0,0 +1,117 @@
+use alloc::{collections::BTreeMap, string::ToString};
+use casper_contract::contract_api::storage;
+use casper_types::{Key, URef};
+
+use crate::utils;
+
+pub enum VoterEvent {
+    GaugeRegistered {
+        pool: Key,
+        gauge: Key,
+        bribe: Key,
+    },
+    Voted {
+        voter: Key,
+        token_id: u64,
+        pool: Key,
+        weight: u128,
+    },
+    Abstained {
+        token_id: u64,
+        pool: Key,
+        weight: u128,
+    },
+    NotifyReward {
+        sender: Key,
+        amount: u128,
+    },
+    DistributeReward {
+        gauge: Key,
+        amount: u128,
+    },
+    Attach {
+        owner: Key,
+        gauge: Key,
+        token_id: u64,
+    },
+    Detach {
+        owner: Key,
+        gauge: Key,
+        token_id: u64,
+    },
+    WhitelistToken {
+        token: Key,
+        allowed: bool,
+    },
+}
+
+pub fn emit(event: &VoterEvent) {
+    let mut param = BTreeMap::new();
+    param.insert("contract_package_hash", utils::get_self_key().to_string());
+    match event {
+        VoterEvent::GaugeRegistered { pool, gauge, bribe } => {
+            param.insert("event_type", "voter_gauge_registered".to_string());
+            param.insert("pool", pool.to_string());
+            param.insert("gauge", gauge.to_string());
+            param.insert("bribe", bribe.to_string());
+        }
+        VoterEvent::Voted {
+            voter,
+            token_id,
+            pool,
+            weight,
+        } => {
+            param.insert("event_type", "voter_voted".to_string());
+            param.insert("voter", voter.to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("pool", pool.to_string());
+            param.insert("weight", weight.to_string());
+        }
+        VoterEvent::Abstained {
+            token_id,
+            pool,
+            weight,
+        } => {
+            param.insert("event_type", "voter_abstained".to_string());
+            param.insert("token_id", token_id.to_string());
+            param.insert("pool", pool.to_string());
+            param.insert("weight", weight.to_string());
+        }
+        VoterEvent::NotifyReward { sender, amount } => {
+            param.insert("event_type", "voter_notify_reward".to_string());
+            param.insert("sender", sender.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        VoterEvent::DistributeReward { gauge, amount } => {
+            param.insert("event_type", "voter_distribute_reward".to_string());
+            param.insert("gauge", gauge.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        VoterEvent::Attach {
+            owner,
+            gauge,
+            token_id,
+        } => {
+            param.insert("event_type", "voter_attach".to_string());
+            param.insert("owner", owner.to_string());
+            param.insert("gauge", gauge.to_string());
+            param.insert("token_id", token_id.to_string());
+        }
+        VoterEvent::Detach {
+            owner,
+            gauge,
+            token_id,
+        } => {
+            param.insert("event_type", "voter_detach".to_string());
+            param.insert("owner", owner.to_string());
+            param.insert("gauge", gauge.to_string());
+            param.insert("token_id", token_id.to_string());
+        }
+        VoterEvent::WhitelistToken { token, allowed } => {
+            param.insert("event_type", "voter_whitelist_token".to_string());
+            param.insert("token", token.to_string());
+            param.insert("allowed", allowed.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(param);
+}
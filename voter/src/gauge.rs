@@ -0,0 +1,20 @@
+//! Calls into gauges, addressed by their package hash
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128};
+
+fn package(gauge: Key) -> ContractPackageHash {
+    ContractPackageHash::new(gauge.into_hash().unwrap_or_revert())
+}
+
+/// @notice Hand `amount` of `token` to the gauge, which pulls it with `transfer_from`
+pub fn notify_reward_amount(gauge: Key, token: Key, amount: u128) {
+    let _: () = runtime::call_versioned_contract(
+        package(gauge),
+        None,
+        "notify_reward_amount",
+        runtime_args! {
+            "token" => token,
+            "amount" => U128::from(amount)
+        },
+    );
+}
@@ -0,0 +1,12 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+
+pub mod bribe;
+pub mod error;
+pub mod escrow;
+pub mod event;
+pub mod gauge;
+pub mod voter;
+
+pub use common::{dict, token, utils};
@@ -0,0 +1,734 @@
+use alloc::{string::ToString, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, U128, U256,
+};
+
+use crate::bribe;
+use crate::dict::{get_key, key_and_value_to_str, set_key, Dict};
+use crate::error::VoterError;
+use crate::escrow;
+use crate::event::{emit, VoterEvent};
+use crate::gauge;
+use crate::token::{self, TokenStandard};
+use crate::utils::{self, current_block_timestamp_seconds, require, WEEK};
+
+pub const VOTING_ESCROW: &str = "voting_escrow";
+pub const TOKEN: &str = "token";
+pub const TEAM: &str = "team";
+pub const POOLS: &str = "pools";
+pub const GAUGES: &str = "gauges";
+pub const BRIBES: &str = "bribes";
+pub const POOL_FOR_GAUGE: &str = "pool_for_gauge";
+pub const WEIGHTS: &str = "weights";
+pub const TOTAL_WEIGHT: &str = "total_weight";
+pub const VOTES: &str = "votes";
+pub const POOL_VOTE: &str = "pool_vote";
+pub const USED_WEIGHTS: &str = "used_weights";
+pub const LAST_VOTED: &str = "last_voted";
+pub const INDEX: &str = "index";
+pub const SUPPLY_INDEX: &str = "supply_index";
+pub const CLAIMABLE: &str = "claimable";
+pub const WHITELISTED: &str = "whitelisted";
+pub const TOKEN_STANDARDS: &str = "token_standards";
+
+pub const ARG_VOTING_ESCROW: &str = "voting_escrow";
+pub const ARG_TOKEN: &str = "token";
+pub const ARG_TEAM: &str = "team";
+pub const ARG_TOKEN_ID: &str = "token_id";
+pub const ARG_POOL: &str = "pool";
+pub const ARG_POOLS: &str = "pools";
+pub const ARG_WEIGHTS: &str = "weights";
+pub const ARG_GAUGE: &str = "gauge";
+pub const ARG_BRIBE: &str = "bribe";
+pub const ARG_AMOUNT: &str = "amount";
+pub const ARG_ACCOUNT: &str = "account";
+pub const ARG_ALLOWED: &str = "allowed";
+pub const ARG_TOKEN_STANDARD: &str = "token_standard";
+
+/// Most pools a single veNFT may split its vote across
+pub const MAX_VOTE_POOLS: usize = 30;
+/// Precision of the reward index
+pub const INDEX_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// @param voting_escrow Package hash of the escrow, this contract must be granted its voter role
+/// @param token Hash of the emitted token, contract hash for ERC-20 and package hash for CEP-18
+/// @param token_standard Standard of the emitted token, as the escrow's install argument
+pub fn init(voting_escrow: Key, token: Key, token_standard: u8) {
+    TokenStandard::require_from_u8(token_standard);
+    set_key(VOTING_ESCROW, voting_escrow);
+    set_key(TOKEN, token);
+    set_key(TEAM, utils::get_immediate_caller_key());
+    set_key(POOLS, Vec::<Key>::new());
+    set_key(TOTAL_WEIGHT, U128::zero());
+    set_key(INDEX, U256::zero());
+    Dict::init(GAUGES);
+    Dict::init(BRIBES);
+    Dict::init(POOL_FOR_GAUGE);
+    Dict::init(WEIGHTS);
+    Dict::init(VOTES);
+    Dict::init(POOL_VOTE);
+    Dict::init(USED_WEIGHTS);
+    Dict::init(LAST_VOTED);
+    Dict::init(SUPPLY_INDEX);
+    Dict::init(CLAIMABLE);
+    Dict::init(WHITELISTED);
+    Dict::init(TOKEN_STANDARDS);
+    Dict::instance(WHITELISTED).set_by_key(&token, true);
+    Dict::instance(TOKEN_STANDARDS).set_by_key(&token, token_standard);
+}
+
+fn voting_escrow() -> Key {
+    get_key(VOTING_ESCROW).unwrap_or_revert()
+}
+
+fn reward_token() -> Key {
+    get_key(TOKEN).unwrap_or_revert()
+}
+
+fn _token_standard(token: &Key) -> TokenStandard {
+    let standard: u8 = Dict::instance(TOKEN_STANDARDS)
+        .get_by_key(token)
+        .unwrap_or_revert_with(VoterError::UnknownTokenStandard);
+    TokenStandard::require_from_u8(standard)
+}
+
+fn only_team() {
+    let team: Key = get_key(TEAM).unwrap_or_revert();
+    require(utils::get_immediate_caller_key() == team, VoterError::NotTeam);
+}
+
+fn only_approved_or_owner(token_id: u64) {
+    require(
+        escrow::is_approved_or_owner(
+            voting_escrow(),
+            utils::get_immediate_caller_key(),
+            token_id,
+        ),
+        VoterError::NotOwnerOrApproved,
+    );
+}
+
+/// @notice A veNFT may vote or reset once per epoch
+fn only_new_epoch(token_id: u64) {
+    let epoch_start = current_block_timestamp_seconds() / WEEK * WEEK;
+    require(epoch_start > _last_voted(token_id), VoterError::AlreadyVotedThisEpoch);
+}
+
+/// `a * b / c` without overflowing the intermediate product
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    (U256::from(a) * U256::from(b) / U256::from(c)).as_u128()
+}
+
+fn total_weight() -> u128 {
+    let total: U128 = get_key(TOTAL_WEIGHT).unwrap_or_revert();
+    total.as_u128()
+}
+
+fn set_total_weight(total: u128) {
+    set_key(TOTAL_WEIGHT, U128::from(total));
+}
+
+fn index() -> U256 {
+    get_key(INDEX).unwrap_or_revert()
+}
+
+fn _gauge(pool: &Key) -> Option<Key> {
+    Dict::instance(GAUGES).get_by_key(pool)
+}
+
+fn _bribe(pool: &Key) -> Key {
+    Dict::instance(BRIBES)
+        .get_by_key(pool)
+        .unwrap_or_revert_with(VoterError::UnknownPool)
+}
+
+fn _weight(pool: &Key) -> u128 {
+    let weight: U128 = Dict::instance(WEIGHTS).get_by_key(pool).unwrap_or_default();
+    weight.as_u128()
+}
+
+fn set_weight(pool: &Key, weight: u128) {
+    Dict::instance(WEIGHTS).set_by_key(pool, U128::from(weight));
+}
+
+fn _votes(token_id: u64, pool: &Key) -> u128 {
+    let votes: U128 = Dict::instance(VOTES)
+        .get(&key_and_value_to_str(pool, &token_id))
+        .unwrap_or_default();
+    votes.as_u128()
+}
+
+fn set_votes(token_id: u64, pool: &Key, votes: u128) {
+    Dict::instance(VOTES).set(&key_and_value_to_str(pool, &token_id), U128::from(votes));
+}
+
+fn _pool_vote(token_id: u64) -> Vec<Key> {
+    Dict::instance(POOL_VOTE)
+        .get(&token_id.to_string())
+        .unwrap_or_default()
+}
+
+fn _last_voted(token_id: u64) -> u64 {
+    Dict::instance(LAST_VOTED)
+        .get(&token_id.to_string())
+        .unwrap_or(0)
+}
+
+fn _claimable(gauge: &Key) -> u128 {
+    let claimable: U128 = Dict::instance(CLAIMABLE).get_by_key(gauge).unwrap_or_default();
+    claimable.as_u128()
+}
+
+////////////////////////////////////////////////////////////////
+//                             GAUGES
+//////////////////////////////////////////////////////////////*/
+/// @notice Whitelist `gauge` as the gauge of `pool`, votes for the pool are recorded in `bribe`
+#[no_mangle]
+pub extern "C" fn register_gauge() {
+    let pool: Key = runtime::get_named_arg(ARG_POOL);
+    let gauge: Key = runtime::get_named_arg(ARG_GAUGE);
+    let bribe: Key = runtime::get_named_arg(ARG_BRIBE);
+    only_team();
+    let pool_for_gauge = Dict::instance(POOL_FOR_GAUGE);
+    require(
+        _gauge(&pool).is_none() && pool_for_gauge.get_by_key::<Key>(&gauge).is_none(),
+        VoterError::GaugeExists,
+    );
+    Dict::instance(GAUGES).set_by_key(&pool, gauge);
+    Dict::instance(BRIBES).set_by_key(&pool, bribe);
+    pool_for_gauge.set_by_key(&gauge, pool);
+    // A new gauge only earns from emissions notified after it was registered
+    Dict::instance(SUPPLY_INDEX).set_by_key(&gauge, index());
+
+    let mut pools: Vec<Key> = get_key(POOLS).unwrap_or_revert();
+    pools.push(pool);
+    set_key(POOLS, pools);
+    emit(&VoterEvent::GaugeRegistered { pool, gauge, bribe });
+}
+
+#[no_mangle]
+pub extern "C" fn set_team() {
+    let team: Key = runtime::get_named_arg(ARG_TEAM);
+    only_team();
+    set_key(TEAM, team);
+}
+
+/// @notice Accept the escrow's voter role after the escrow proposed this contract for it
+#[no_mangle]
+pub extern "C" fn accept_voter_role() {
+    only_team();
+    escrow::accept_voter(voting_escrow());
+}
+
+/// @notice Allow or disallow `token` as a new bribe reward, the emitted token starts allowed
+/// @param token_standard Standard bribes use to move `token`, as the escrow's install argument
+#[no_mangle]
+pub extern "C" fn whitelist_token() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let allowed: bool = runtime::get_named_arg(ARG_ALLOWED);
+    let token_standard: u8 = runtime::get_named_arg(ARG_TOKEN_STANDARD);
+    only_team();
+    TokenStandard::require_from_u8(token_standard);
+    Dict::instance(WHITELISTED).set_by_key(&token, allowed);
+    Dict::instance(TOKEN_STANDARDS).set_by_key(&token, token_standard);
+    emit(&VoterEvent::WhitelistToken { token, allowed });
+}
+
+#[no_mangle]
+pub extern "C" fn is_whitelisted() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let allowed: bool = Dict::instance(WHITELISTED).get_by_key(&token).unwrap_or(false);
+    runtime::ret(CLValue::from_t(allowed).unwrap_or_revert());
+}
+
+/// @notice Standard of a token that was ever whitelisted, reverts for any other token
+#[no_mangle]
+pub extern "C" fn token_standard() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let standard = _token_standard(&token);
+    runtime::ret(CLValue::from_t(standard as u8).unwrap_or_revert());
+}
+
+/// @notice Accrue the share of notified emissions owed to `gauge` by the weight of its pool
+fn _update_for(gauge: &Key) {
+    let pool: Key = Dict::instance(POOL_FOR_GAUGE)
+        .get_by_key(gauge)
+        .unwrap_or_revert_with(VoterError::NotGauge);
+    let supply_index_dict = Dict::instance(SUPPLY_INDEX);
+    let index = index();
+    let supplied = _weight(&pool);
+    if supplied > 0 {
+        let supply_index: U256 = supply_index_dict.get_by_key(gauge).unwrap_or_default();
+        let delta = index - supply_index;
+        if delta > U256::zero() {
+            let share = U256::from(supplied) * delta / U256::from(INDEX_PRECISION);
+            Dict::instance(CLAIMABLE)
+                .set_by_key(gauge, U128::from(_claimable(gauge) + share.as_u128()));
+        }
+    }
+    supply_index_dict.set_by_key(gauge, index);
+}
+
+/// @notice Pull `amount` of the emitted token from the caller (the minter) and
+///         index it against the current total weight
+#[no_mangle]
+pub extern "C" fn notify_reward_amount() {
+    let amount: U128 = runtime::get_named_arg(ARG_AMOUNT);
+    let amount = amount.as_u128();
+    let sender = utils::get_immediate_caller_key();
+    let token = reward_token();
+    token::transfer_from(
+        token,
+        _token_standard(&token),
+        sender,
+        utils::get_self_key(),
+        amount,
+    );
+
+    let total = total_weight();
+    if total > 0 {
+        let ratio = U256::from(amount) * U256::from(INDEX_PRECISION) / U256::from(total);
+        if ratio > U256::zero() {
+            set_key(INDEX, index() + ratio);
+        }
+    }
+    emit(&VoterEvent::NotifyReward { sender, amount });
+}
+
+/// @notice Send the emissions accrued by `gauge` to it
+#[no_mangle]
+pub extern "C" fn distribute() {
+    let gauge: Key = runtime::get_named_arg(ARG_GAUGE);
+    _update_for(&gauge);
+    let claimable = _claimable(&gauge);
+    if claimable > 0 {
+        Dict::instance(CLAIMABLE).set_by_key(&gauge, U128::zero());
+        let token = reward_token();
+        token::approve(token, _token_standard(&token), gauge, claimable);
+        gauge::notify_reward_amount(gauge, token, claimable);
+        emit(&VoterEvent::DistributeReward {
+            gauge,
+            amount: claimable,
+        });
+    }
+}
+
+/// @notice Called by a gauge when `account` stakes with a veNFT boost
+#[no_mangle]
+pub extern "C" fn attach_token_to_gauge() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    let gauge = utils::get_immediate_caller_key();
+    require(
+        Dict::instance(POOL_FOR_GAUGE).get_by_key::<Key>(&gauge).is_some(),
+        VoterError::NotGauge,
+    );
+    if token_id > 0 {
+        escrow::attach(voting_escrow(), token_id);
+    }
+    emit(&VoterEvent::Attach {
+        owner: account,
+        gauge,
+        token_id,
+    });
+}
+
+/// @notice Called by a gauge when `account` removes its veNFT boost
+#[no_mangle]
+pub extern "C" fn detach_token_from_gauge() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let account: Key = runtime::get_named_arg(ARG_ACCOUNT);
+    let gauge = utils::get_immediate_caller_key();
+    require(
+        Dict::instance(POOL_FOR_GAUGE).get_by_key::<Key>(&gauge).is_some(),
+        VoterError::NotGauge,
+    );
+    if token_id > 0 {
+        escrow::detach(voting_escrow(), token_id);
+    }
+    emit(&VoterEvent::Detach {
+        owner: account,
+        gauge,
+        token_id,
+    });
+}
+
+////////////////////////////////////////////////////////////////
+//                             VOTING
+//////////////////////////////////////////////////////////////*/
+/// @notice Remove every vote of `token_id` and release it in the escrow
+fn _reset(token_id: u64) {
+    let mut total = 0u128;
+    for pool in _pool_vote(token_id).iter() {
+        let votes = _votes(token_id, pool);
+        if votes > 0 {
+            _update_for(&_gauge(pool).unwrap_or_revert_with(VoterError::UnknownPool));
+            set_weight(pool, _weight(pool) - votes);
+            set_votes(token_id, pool, 0);
+            bribe::withdraw(_bribe(pool), votes, token_id);
+            total += votes;
+            emit(&VoterEvent::Abstained {
+                token_id,
+                pool: *pool,
+                weight: votes,
+            });
+        }
+    }
+    set_total_weight(total_weight() - total);
+    Dict::instance(USED_WEIGHTS).set(&token_id.to_string(), U128::zero());
+    Dict::instance(POOL_VOTE).set(&token_id.to_string(), Vec::<Key>::new());
+    escrow::abstain(voting_escrow(), token_id);
+}
+
+/// @notice Split the current voting power of `token_id` over `pools`, proportionally to `weights`
+fn _vote(token_id: u64, pools: &[Key], weights: &[u128]) {
+    _reset(token_id);
+    let escrow = voting_escrow();
+    let weight = escrow::balance_of_nft(escrow, token_id);
+    let total_vote_weight = weights
+        .iter()
+        .try_fold(0u128, |total, pool_share| total.checked_add(*pool_share))
+        .unwrap_or_revert_with(VoterError::WeightOverflow);
+    require(total_vote_weight > 0, VoterError::ZeroVote);
+
+    let voter = utils::get_immediate_caller_key();
+    let mut used_weight = 0u128;
+    let mut voted_pools = Vec::with_capacity(pools.len());
+    for (pool, pool_share) in pools.iter().zip(weights.iter()) {
+        let gauge = _gauge(pool).unwrap_or_revert_with(VoterError::UnknownPool);
+        let pool_weight = mul_div(*pool_share, weight, total_vote_weight);
+        require(_votes(token_id, pool) == 0, VoterError::DuplicateVote);
+        require(pool_weight > 0, VoterError::ZeroVote);
+        _update_for(&gauge);
+
+        voted_pools.push(*pool);
+        set_weight(pool, _weight(pool) + pool_weight);
+        set_votes(token_id, pool, pool_weight);
+        bribe::deposit(_bribe(pool), pool_weight, token_id);
+        used_weight += pool_weight;
+        emit(&VoterEvent::Voted {
+            voter,
+            token_id,
+            pool: *pool,
+            weight: pool_weight,
+        });
+    }
+    require(used_weight <= weight, VoterError::VoteExceedsBalance);
+    if used_weight > 0 {
+        escrow::voting(escrow, token_id);
+    }
+    set_total_weight(total_weight() + used_weight);
+    Dict::instance(USED_WEIGHTS).set(&token_id.to_string(), U128::from(used_weight));
+    Dict::instance(POOL_VOTE).set(&token_id.to_string(), voted_pools);
+}
+
+/// @param pools Pools to vote for, each must have a registered gauge
+/// @param weights Relative share of the voting power given to each pool
+#[no_mangle]
+pub extern "C" fn vote() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let pools: Vec<Key> = runtime::get_named_arg(ARG_POOLS);
+    let weights: Vec<U128> = runtime::get_named_arg(ARG_WEIGHTS);
+    require(pools.len() == weights.len(), VoterError::LengthMismatch);
+    require(pools.len() <= MAX_VOTE_POOLS, VoterError::TooManyPools);
+    only_new_epoch(token_id);
+    only_approved_or_owner(token_id);
+
+    Dict::instance(LAST_VOTED).set(&token_id.to_string(), current_block_timestamp_seconds());
+    let weights: Vec<u128> = weights.iter().map(|weight| weight.as_u128()).collect();
+    _vote(token_id, &pools, &weights);
+}
+
+#[no_mangle]
+pub extern "C" fn reset() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    only_new_epoch(token_id);
+    only_approved_or_owner(token_id);
+    _reset(token_id);
+}
+
+/// @notice Recast the existing votes of `token_id` with its current voting power
+#[no_mangle]
+pub extern "C" fn poke() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    only_approved_or_owner(token_id);
+    let pools = _pool_vote(token_id);
+    if pools.is_empty() {
+        return;
+    }
+    let weights: Vec<u128> = pools.iter().map(|pool| _votes(token_id, pool)).collect();
+    _vote(token_id, &pools, &weights);
+}
+
+////////////////////////////////////////////////////////////////
+//                             VIEWS
+//////////////////////////////////////////////////////////////*/
+#[no_mangle]
+pub extern "C" fn gauges() {
+    let pool: Key = runtime::get_named_arg(ARG_POOL);
+    runtime::ret(CLValue::from_t(_gauge(&pool)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn bribes() {
+    let pool: Key = runtime::get_named_arg(ARG_POOL);
+    let bribe: Option<Key> = Dict::instance(BRIBES).get_by_key(&pool);
+    runtime::ret(CLValue::from_t(bribe).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn pools() {
+    let pools: Vec<Key> = get_key(POOLS).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(pools).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn weights() {
+    let pool: Key = runtime::get_named_arg(ARG_POOL);
+    runtime::ret(CLValue::from_t(U128::from(_weight(&pool))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_total_weight() {
+    runtime::ret(CLValue::from_t(U128::from(total_weight())).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn votes() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let pool: Key = runtime::get_named_arg(ARG_POOL);
+    runtime::ret(CLValue::from_t(U128::from(_votes(token_id, &pool))).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn used_weights() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    let used: U128 = Dict::instance(USED_WEIGHTS)
+        .get(&token_id.to_string())
+        .unwrap_or_default();
+    runtime::ret(CLValue::from_t(used).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn pool_vote() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    runtime::ret(CLValue::from_t(_pool_vote(token_id)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn last_voted() {
+    let token_id: u64 = runtime::get_named_arg::<U256>(ARG_TOKEN_ID).as_u64();
+    runtime::ret(CLValue::from_t(_last_voted(token_id)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn claimable() {
+    let gauge: Key = runtime::get_named_arg(ARG_GAUGE);
+    runtime::ret(CLValue::from_t(U128::from(_claimable(&gauge))).unwrap_or_revert());
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "register_gauge",
+        vec![
+            Parameter::new(ARG_POOL, Key::cl_type()),
+            Parameter::new(ARG_GAUGE, Key::cl_type()),
+            Parameter::new(ARG_BRIBE, Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_team",
+        vec![Parameter::new(ARG_TEAM, Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "accept_voter_role",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "whitelist_token",
+        vec![
+            Parameter::new(ARG_TOKEN, Key::cl_type()),
+            Parameter::new(ARG_ALLOWED, bool::cl_type()),
+            Parameter::new(ARG_TOKEN_STANDARD, u8::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_whitelisted",
+        vec![Parameter::new(ARG_TOKEN, Key::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "token_standard",
+        vec![Parameter::new(ARG_TOKEN, Key::cl_type())],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "notify_reward_amount",
+        vec![Parameter::new(ARG_AMOUNT, U128::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "distribute",
+        vec![Parameter::new(ARG_GAUGE, Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "attach_token_to_gauge",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "detach_token_from_gauge",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_ACCOUNT, Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "vote",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_POOLS, Vec::<Key>::cl_type()),
+            Parameter::new(ARG_WEIGHTS, Vec::<U128>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for name in ["reset", "poke"].iter() {
+        entry_points.add_entry_point(EntryPoint::new(
+            *name,
+            vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "gauges",
+        vec![Parameter::new(ARG_POOL, Key::cl_type())],
+        Option::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "bribes",
+        vec![Parameter::new(ARG_POOL, Key::cl_type())],
+        Option::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "pools",
+        vec![],
+        Vec::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "weights",
+        vec![Parameter::new(ARG_POOL, Key::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_total_weight",
+        vec![],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "votes",
+        vec![
+            Parameter::new(ARG_TOKEN_ID, U256::cl_type()),
+            Parameter::new(ARG_POOL, Key::cl_type()),
+        ],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "used_weights",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "pool_vote",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        Vec::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "last_voted",
+        vec![Parameter::new(ARG_TOKEN_ID, U256::cl_type())],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claimable",
+        vec![Parameter::new(ARG_GAUGE, Key::cl_type())],
+        U128::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}